@@ -1,7 +1,12 @@
-use pciid_parser::Database;
-use std::io::Cursor;
+use divan::AllocProfiler;
+use pciid_parser::{BorrowedDatabase, Database};
+use std::{fs::File, io::Cursor};
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
 
 const DB_DATA: &[u8] = include_bytes!("../tests/pci.ids");
+const DB_STR: &str = include_str!("../tests/pci.ids");
 
 fn main() {
     divan::main();
@@ -13,6 +18,11 @@ fn parse_embedded() -> Database {
     Database::parse_db(cursor).unwrap()
 }
 
+#[divan::bench]
+fn parse_embedded_borrowed() -> BorrowedDatabase<'static> {
+    BorrowedDatabase::parse_str(DB_STR).unwrap()
+}
+
 #[divan::bench]
 fn find_polaris() -> Option<String> {
     let cursor = Cursor::new(DB_DATA);
@@ -27,5 +37,7 @@ fn find_end() -> Option<String> {
 
 #[divan::bench]
 fn find_end_in_file() -> Option<String> {
-    pciid_parser::find_device_name(0x1fc9, 0x3010).unwrap()
+    // `find_device_name` would only read the file once and then use the global database
+    let file = File::open(Database::default_path().unwrap()).unwrap();
+    pciid_parser::find_device_name_with_reader(file, 0x1fc9, 0x3010).unwrap()
 }
@@ -0,0 +1,217 @@
+//! Generates the static tables for the `embedded` feature with the crate's own parser
+extern crate alloc;
+
+#[allow(dead_code)]
+#[path = "src/error/parse.rs"]
+mod parse_error;
+#[allow(dead_code)]
+#[path = "src/parser.rs"]
+mod parser;
+#[allow(dead_code)]
+#[path = "src/schema.rs"]
+mod schema;
+
+/// The parser only needs the parse errors, `Error` itself refers to optional dependencies
+mod error {
+    pub use crate::parse_error::{ErrorKind, ParseError};
+    use std::fmt::{self, Display};
+
+    #[derive(Debug)]
+    pub enum Error {
+        Parse(ParseError),
+        InvalidUtf8(core::str::Utf8Error),
+        Io(std::io::Error),
+    }
+
+    impl From<std::io::Error> for Error {
+        fn from(error: std::io::Error) -> Self {
+            Self::Io(error)
+        }
+    }
+
+    impl Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Error::Parse(err) => write!(f, "parsing error: {err}"),
+                Error::InvalidUtf8(err) => write!(f, "invalid utf-8: {err}"),
+                Error::Io(err) => write!(f, "io error: {err}"),
+            }
+        }
+    }
+}
+
+use parser::{Event, StrParser};
+use schema::Metadata;
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Files of the crate that are compiled into the build script
+const SHARED_SOURCES: &[&str] = &["src/error/parse.rs", "src/parser.rs", "src/schema.rs"];
+/// Database that is embedded when `PCIIDS_EMBED_PATH` is not set
+const BUNDLED_PATH: &str = "tests/pci.ids";
+
+#[derive(Default)]
+struct Entry<K, C> {
+    name: String,
+    children: BTreeMap<K, C>,
+}
+
+type Vendors = BTreeMap<u16, Entry<u16, Entry<(u16, u16), String>>>;
+type Classes = BTreeMap<u8, Entry<u8, Entry<u8, String>>>;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    // The modules shared with the crate generate the tables as well
+    for source in SHARED_SOURCES {
+        println!("cargo:rerun-if-changed={source}");
+    }
+    println!("cargo:rerun-if-env-changed=PCIIDS_EMBED_PATH");
+    if env::var_os("CARGO_FEATURE_EMBEDDED").is_none() {
+        return;
+    }
+
+    let path =
+        env::var_os("PCIIDS_EMBED_PATH").map_or_else(|| PathBuf::from(BUNDLED_PATH), PathBuf::from);
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let input = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("could not read {}: {err}", path.display()));
+    let (vendors, classes, metadata) = parse(&input, &path);
+
+    let out_path = Path::new(&env::var_os("OUT_DIR").unwrap()).join("embedded.rs");
+    fs::write(out_path, generate(&vendors, &classes, &metadata)).unwrap();
+}
+
+fn parse(input: &str, path: &Path) -> (Vendors, Classes, Metadata) {
+    let mut vendors = Vendors::new();
+    let mut classes = Classes::new();
+    let (mut vendor_id, mut device_id, mut class_id, mut subclass_id) = (None, None, None, None);
+
+    let mut parser = StrParser::new(input);
+    for event in parser.by_ref() {
+        let event = event.unwrap_or_else(|err| panic!("could not parse {}: {err}", path.display()));
+        let name = event.name().to_owned();
+
+        // The parser only produces nested entries after their parents
+        match event {
+            Event::Vendor { id, .. } => {
+                vendor_id = Some(id);
+                vendors.entry(id).or_default().name = name;
+            }
+            Event::Device { id, .. } => {
+                device_id = Some(id);
+                let vendor = vendors.get_mut(&vendor_id.unwrap()).unwrap();
+                vendor.children.entry(id).or_default().name = name;
+            }
+            Event::Subdevice {
+                subvendor,
+                subdevice,
+                ..
+            } => {
+                let vendor = vendors.get_mut(&vendor_id.unwrap()).unwrap();
+                let device = vendor.children.get_mut(&device_id.unwrap()).unwrap();
+                device.children.insert((subvendor, subdevice), name);
+            }
+            Event::Class { id, .. } => {
+                class_id = Some(id);
+                classes.entry(id).or_default().name = name;
+            }
+            Event::SubClass { id, .. } => {
+                subclass_id = Some(id);
+                let class = classes.get_mut(&class_id.unwrap()).unwrap();
+                class.children.entry(id).or_default().name = name;
+            }
+            Event::ProgIf { id, .. } => {
+                let class = classes.get_mut(&class_id.unwrap()).unwrap();
+                let subclass = class.children.get_mut(&subclass_id.unwrap()).unwrap();
+                subclass.children.insert(id, name);
+            }
+        }
+    }
+
+    (vendors, classes, parser.metadata().clone())
+}
+
+fn generate(vendors: &Vendors, classes: &Classes, metadata: &Metadata) -> String {
+    let mut out = String::new();
+
+    out.push_str("/// The database that was embedded at build time\n");
+    out.push_str("pub static EMBEDDED: StaticDatabase = StaticDatabase {\nvendors: &[\n");
+    for (id, vendor) in vendors {
+        write!(
+            out,
+            "StaticVendor {{ id: {id:#06x}, name: {:?}, devices: &[",
+            vendor.name
+        )
+        .unwrap();
+        for (id, device) in &vendor.children {
+            write!(
+                out,
+                "StaticDevice {{ id: {id:#06x}, name: {:?}, subdevices: &[",
+                device.name
+            )
+            .unwrap();
+            for ((subvendor, subdevice), name) in &device.children {
+                write!(
+                    out,
+                    "StaticSubdevice {{ subvendor: {subvendor:#06x}, subdevice: {subdevice:#06x}, name: {:?} }},",
+                    name
+                )
+                .unwrap();
+            }
+            out.push_str("] },");
+        }
+        out.push_str("] },\n");
+    }
+
+    out.push_str("],\nclasses: &[\n");
+    for (id, class) in classes {
+        write!(
+            out,
+            "StaticClass {{ id: {id:#04x}, name: {:?}, subclasses: &[",
+            class.name
+        )
+        .unwrap();
+        for (id, subclass) in &class.children {
+            write!(
+                out,
+                "StaticSubClass {{ id: {id:#04x}, name: {:?}, prog_ifs: &[",
+                subclass.name
+            )
+            .unwrap();
+            for (id, name) in &subclass.children {
+                write!(out, "StaticProgIf {{ id: {id:#04x}, name: {:?} }},", name).unwrap();
+            }
+            out.push_str("] },");
+        }
+        out.push_str("] },\n");
+    }
+
+    out.push_str("],\nmetadata: Metadata {\n");
+    match metadata.version {
+        Some(version) => writeln!(
+            out,
+            "version: Some(Version {{ year: {}, month: {}, day: {} }}),",
+            version.year, version.month, version.day
+        ),
+        None => writeln!(out, "version: None,"),
+    }
+    .unwrap();
+    match metadata.date {
+        Some(date) => writeln!(
+            out,
+            "date: Some(Timestamp {{ year: {}, month: {}, day: {}, hour: {}, minute: {}, second: {} }}),",
+            date.year, date.month, date.day, date.hour, date.minute, date.second
+        ),
+        None => writeln!(out, "date: None,"),
+    }
+    .unwrap();
+    out.push_str("},\n};\n");
+
+    out
+}
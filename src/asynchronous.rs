@@ -0,0 +1,181 @@
+//! Asynchronous reading and parsing of the database for tokio users.
+//!
+//! ```rust,no_run
+//! use pciid_parser::Database;
+//!
+//! # async fn example() {
+//! let db = Database::read_async().await.unwrap();
+//! # }
+//! ```
+use crate::{
+    builder::Builder,
+    compression::{self, Compression},
+    parser::{trim_line_end, Event, LineParser, ParseOptions, Warning},
+    schema::Metadata,
+    Database, Error,
+};
+use std::{io::Cursor, path::Path};
+use tokio::{
+    fs::File,
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, BufReader},
+};
+
+impl Database {
+    /// Asynchronously read the database from the same file as [`Database::read`]
+    ///
+    /// # Errors
+    /// Returns an error when either no file could be found or the parsing fails.
+    pub async fn read_async() -> Result<Self, Error> {
+        let path = Self::default_path()?;
+        Self::read_from_file_async(path).await
+    }
+
+    /// Asynchronously read the database from a given path.
+    /// Compressed files are detected automatically when the matching feature is enabled,
+    /// and are decompressed and parsed on a blocking thread of the tokio runtime.
+    ///
+    /// # Errors
+    /// Returns an error when the file can't be read or decompressed or when parsing fails
+    ///
+    /// # Panics
+    /// Panics when a compressed file is read outside of a tokio runtime
+    pub async fn read_from_file_async<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path).await?;
+        let mut reader = BufReader::new(file);
+
+        if Compression::detect(reader.fill_buf().await?).is_some() {
+            // The decoders are synchronous, so the file is read into memory and decoded on a blocking thread
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data).await?;
+            return spawn_blocking(move || {
+                Self::parse_db(compression::decompress(Cursor::new(data))?)
+            })
+            .await;
+        }
+
+        Self::parse_db_async(reader).await
+    }
+
+    /// Fetch a database from an online source without blocking the async runtime.
+    /// See [`OnlineSource::fetch_async`](crate::online::OnlineSource::fetch_async) for configuring the source.
+    ///
+    /// # Errors
+    /// Returns an error when the database either can't be fetched or parsed
+    #[cfg(feature = "online")]
+    pub async fn get_online_async() -> Result<Self, Error> {
+        crate::online::OnlineSource::new().fetch_async().await
+    }
+
+    /// Parse a database from the given asynchronous reader
+    ///
+    /// # Errors
+    /// Returns an error whenever there's a parsing error
+    pub async fn parse_db_async<R>(reader: R) -> Result<Self, Error>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let (db, _) = Self::parse_db_async_with_options(reader, ParseOptions::default()).await?;
+        Ok(db)
+    }
+
+    /// Parse a database from the given asynchronous reader with custom options.
+    /// In lenient mode the lines that were skipped are returned alongside the database.
+    ///
+    /// # Errors
+    /// Returns an error when reading fails or, in strict mode, whenever there's a parsing error
+    pub async fn parse_db_async_with_options<R>(
+        reader: R,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Warning>), Error>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let mut parser = AsyncParser::with_options(reader, options);
+        let mut builder = Builder::new();
+
+        while let Some(event) = parser.next_event().await? {
+            builder.push(event);
+        }
+
+        let db = builder.finish(parser.metadata().clone());
+        Ok((db, parser.take_warnings()))
+    }
+}
+
+/// Run a synchronous task on a blocking thread of the tokio runtime, resuming its panic if it panicked
+pub(crate) async fn spawn_blocking<T, F>(task: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(task).await {
+        Ok(result) => result,
+        Err(err) => match err.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(err) => Err(Error::Io(std::io::Error::other(err))),
+        },
+    }
+}
+
+/// Pull-parser over an asynchronous reader, such as a [`tokio::fs::File`] in a [`tokio::io::BufReader`].
+///
+/// It works like [`Parser`](crate::parser::Parser), except that [`AsyncParser::next_event`] awaits the next line.
+pub struct AsyncParser<R> {
+    reader: R,
+    buf: String,
+    lines: LineParser,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncParser<R> {
+    /// Create a strict parser over the given reader
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// Create a parser over the given reader with custom options
+    pub fn with_options(reader: R, options: ParseOptions) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+            lines: LineParser::new(options),
+        }
+    }
+
+    /// Malformed lines that have been skipped so far. Always empty in strict mode.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.lines.warnings
+    }
+
+    /// Information from the header comments that have been read so far
+    pub fn metadata(&self) -> &Metadata {
+        &self.lines.metadata
+    }
+
+    /// Take the collected warnings out of the parser
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        core::mem::take(&mut self.lines.warnings)
+    }
+
+    /// Read the next entry from the database, skipping comments and empty lines.
+    /// Returns `None` once the end of the input has been reached.
+    ///
+    /// # Errors
+    /// Returns an error when reading fails or, in strict mode, when a line is malformed
+    pub async fn next_event(&mut self) -> Result<Option<Event<'_>>, Error> {
+        let (event, name_start) = loop {
+            self.buf.clear();
+            if self.reader.read_line(&mut self.buf).await? == 0 {
+                return Ok(None);
+            }
+
+            if let Some(event) = self.lines.parse(&self.buf)? {
+                // Detached from the buffer for the same reason as in `Parser::next_event`
+                let line = trim_line_end(&self.buf);
+                break (event.with_name(""), line.len() - event.name().len());
+            }
+        };
+
+        let line = trim_line_end(&self.buf);
+        Ok(Some(event.with_name(&line[name_start..])))
+    }
+}
@@ -0,0 +1,337 @@
+//! Command-line lookups in the PCI ID database
+use clap::{Parser, Subcommand, ValueEnum};
+use pciid_parser::{
+    display::{self, Options},
+    modalias::Modalias,
+    schema::EntryId,
+    Database, Error,
+};
+use serde_json::{json, Map, Value};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
+
+#[derive(Parser)]
+#[command(version, about = "Look up names in the PCI ID database")]
+struct Args {
+    /// Database file to read instead of the default locations
+    #[arg(long, global = true)]
+    db: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Look up a vendor, device and optionally a subsystem, such as `1002:67df:1da2:e387`
+    Lookup { ids: Ids },
+    /// Look up a class, subclass and programming interface from a hex code such as `0300`
+    Class {
+        #[arg(value_parser = parse_class)]
+        code: (u32, usize),
+    },
+    /// Find entries whose names contain the text
+    Search {
+        text: String,
+        /// Rank devices and subsystems by similarity instead of matching the text exactly
+        #[arg(long)]
+        fuzzy: bool,
+    },
+    /// Describe the device from a kernel modalias string
+    Modalias { modalias: Modalias },
+    /// Show the version and size of the database
+    Info,
+    /// Write the whole database in another format
+    Convert {
+        #[arg(long, value_enum)]
+        to: Format,
+    },
+}
+
+/// Vendor and device ids, optionally followed by subsystem ids
+#[derive(Clone)]
+struct Ids(Vec<u16>);
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Nested objects keyed by hex ids
+    Json,
+    /// The `pci.ids` text format
+    Ids,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let path = match args.db.map_or_else(Database::default_path, Ok) {
+        Ok(path) => path,
+        Err(err) => {
+            eprintln!("Could not find the database: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let db = match Database::read_from_file(&path) {
+        Ok(db) => db,
+        Err(err) => {
+            eprintln!("Could not read {}: {err}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&db, &path, args.command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        // Such as a closed pipe
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the command, returning whether anything was found
+fn run(db: &Database, path: &Path, command: Command) -> io::Result<bool> {
+    let mut out = io::stdout().lock();
+
+    match command {
+        Command::Lookup { ids: Ids(ids) } => {
+            let id = |index: usize| ids.get(index).copied().unwrap_or_default();
+            let info = db.get_device_info(id(0), id(1), id(2), id(3));
+
+            let lines = [
+                ("Vendor", info.vendor_name),
+                ("Device", info.device_name),
+                ("Subvendor", info.subvendor_name),
+                ("Subdevice", info.subdevice_name),
+            ];
+            print_names(&mut out, &lines[..ids.len()])
+        }
+        Command::Class {
+            code: (code, digits),
+        } => {
+            let info = db.get_class_info_by_code(code);
+
+            let lines = [
+                ("Class", info.class_name),
+                ("Subclass", info.subclass_name),
+                ("Prog-if", info.prog_if_name),
+            ];
+            print_names(&mut out, &lines[..digits / 2])
+        }
+        Command::Search { text, fuzzy } => {
+            let mut found = false;
+            if fuzzy {
+                for result in db.fuzzy_search(&text) {
+                    found = true;
+                    writeln!(
+                        out,
+                        "{}  {} ({})",
+                        format_entry(result.entry),
+                        result.name,
+                        result.matched
+                    )?;
+                }
+            } else {
+                for result in db.search(&text) {
+                    found = true;
+                    writeln!(out, "{}  {}", format_entry(result.entry), result.name)?;
+                }
+            }
+            Ok(found)
+        }
+        Command::Modalias { modalias } => {
+            let info = db.lookup_modalias(&modalias);
+            let class_code = modalias.class_code();
+
+            writeln!(
+                out,
+                "Device:    {}",
+                display::device(
+                    modalias.vendor_id,
+                    modalias.device_id,
+                    &info.device_info,
+                    Options::numeric()
+                )
+            )?;
+            writeln!(
+                out,
+                "Subsystem: {}",
+                display::subsystem(
+                    modalias.vendor_id,
+                    modalias.device_id,
+                    modalias.subsystem_vendor_id,
+                    modalias.subsystem_device_id,
+                    &info.device_info,
+                    Options::numeric()
+                )
+            )?;
+            writeln!(
+                out,
+                "Class:     {}",
+                display::class(class_code, &info.class_info, Options::numeric())
+            )?;
+            if let Some(name) = info.class_info.prog_if_name {
+                writeln!(out, "Prog-if:   {name} [{:02x}]", modalias.prog_if)?;
+            }
+            Ok(info.device_info.vendor_name.is_some())
+        }
+        Command::Info => {
+            let devices = db.vendors.values().map(|vendor| vendor.devices.len());
+            let subsystems = db.vendors.values().flat_map(|vendor| {
+                vendor
+                    .devices
+                    .values()
+                    .map(|device| device.subdevices.len())
+            });
+
+            if let Some(version) = db.metadata.version {
+                writeln!(out, "Version:    {version}")?;
+            }
+            if let Some(date) = db.metadata.date {
+                writeln!(out, "Date:       {date}")?;
+            }
+            writeln!(out, "Vendors:    {}", db.vendors.len())?;
+            writeln!(out, "Devices:    {}", devices.sum::<usize>())?;
+            writeln!(out, "Subsystems: {}", subsystems.sum::<usize>())?;
+            writeln!(out, "Classes:    {}", db.classes.len())?;
+            writeln!(out, "Source:     {}", path.display())?;
+            Ok(true)
+        }
+        Command::Convert { to: Format::Json } => {
+            serde_json::to_writer_pretty(&mut out, &to_json(db))?;
+            writeln!(out)?;
+            Ok(true)
+        }
+        Command::Convert { to: Format::Ids } => {
+            db.write_to(out).map_err(|err| match err {
+                Error::Io(err) => err,
+                err => io::Error::other(err),
+            })?;
+            Ok(true)
+        }
+    }
+}
+
+/// Print the names that were asked for, returning whether all of them are known
+fn print_names(out: &mut impl Write, lines: &[(&str, Option<&str>)]) -> io::Result<bool> {
+    let mut found = true;
+    for (label, name) in lines {
+        let label = format!("{label}:");
+        match name {
+            Some(name) => writeln!(out, "{label:<11}{name}")?,
+            None => {
+                writeln!(out, "{label:<11}unknown")?;
+                found = false;
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Format the ids of an entry the way they are written on the command line
+fn format_entry(entry: EntryId) -> String {
+    match entry {
+        EntryId::Vendor(vendor) => format!("{vendor:04x}"),
+        EntryId::Device { vendor, device } => format!("{vendor:04x}:{device:04x}"),
+        EntryId::Subdevice {
+            vendor,
+            device,
+            subvendor,
+            subdevice,
+        } => format!("{vendor:04x}:{device:04x}:{subvendor:04x}:{subdevice:04x}"),
+        EntryId::Class(class) => format!("C {class:02x}"),
+        EntryId::SubClass { class, subclass } => format!("C {class:02x}{subclass:02x}"),
+        EntryId::ProgIf {
+            class,
+            subclass,
+            prog_if,
+        } => format!("C {class:02x}{subclass:02x}{prog_if:02x}"),
+    }
+}
+
+fn to_json(db: &Database) -> Value {
+    let vendors: Map<_, _> = db
+        .vendors
+        .iter()
+        .map(|(id, vendor)| {
+            let devices: Map<_, _> = vendor
+                .devices
+                .iter()
+                .map(|(id, device)| {
+                    let subsystems: Map<_, _> = device
+                        .subdevices
+                        .iter()
+                        .map(|(id, name)| {
+                            let key = format!("{:04x}:{:04x}", id.subvendor, id.subdevice);
+                            (key, json!(name))
+                        })
+                        .collect();
+                    let value = json!({ "name": device.name, "subsystems": subsystems });
+                    (format!("{id:04x}"), value)
+                })
+                .collect();
+            let value = json!({ "name": vendor.name, "devices": devices });
+            (format!("{id:04x}"), value)
+        })
+        .collect();
+
+    let classes: Map<_, _> = db
+        .classes
+        .iter()
+        .map(|(id, class)| {
+            let subclasses: Map<_, _> = class
+                .subclasses
+                .iter()
+                .map(|(id, subclass)| {
+                    let prog_ifs: Map<_, _> = subclass
+                        .prog_ifs
+                        .iter()
+                        .map(|(id, name)| (format!("{id:02x}"), json!(name)))
+                        .collect();
+                    let value = json!({ "name": subclass.name, "prog_ifs": prog_ifs });
+                    (format!("{id:02x}"), value)
+                })
+                .collect();
+            let value = json!({ "name": class.name, "subclasses": subclasses });
+            (format!("{id:02x}"), value)
+        })
+        .collect();
+
+    json!({
+        "version": db.metadata.version.map(|version| version.to_string()),
+        "date": db.metadata.date.map(|date| date.to_string()),
+        "vendors": vendors,
+        "classes": classes,
+    })
+}
+
+impl FromStr for Ids {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let ids = value
+            .split(':')
+            .map(|id| u16::from_str_radix(id, 16).map_err(|_| format!("invalid hex id {id:?}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match ids.len() {
+            2 | 4 => Ok(Self(ids)),
+            _ => Err("expected vendor:device or vendor:device:subvendor:subdevice".to_owned()),
+        }
+    }
+}
+
+/// Parse a class, class and subclass, or full class code, along with its number of digits
+fn parse_class(value: &str) -> Result<(u32, usize), String> {
+    if !matches!(value.len(), 2 | 4 | 6) {
+        return Err("expected 2, 4 or 6 hex digits".to_owned());
+    }
+
+    let code = u32::from_str_radix(value, 16).map_err(|_| format!("invalid hex code {value:?}"))?;
+    // Align to a full class code, so `03` becomes `030000`
+    Ok((code << (4 * (6 - value.len())), value.len()))
+}
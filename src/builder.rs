@@ -0,0 +1,146 @@
+#[cfg(feature = "std")]
+use crate::overlay::OverlaySources;
+use crate::{
+    parser::Event,
+    schema::{Class, Device, Metadata, SubClass, SubDeviceId, Vendor},
+    Database,
+};
+use alloc::collections::BTreeMap;
+
+/// Assembles a [`Database`] from parser events.
+///
+/// Names are converted with `From<&str>`, so the same code builds owned and borrowed databases.
+pub(crate) struct Builder<S> {
+    current_vendor: Option<(u16, Vendor<S>)>,
+    current_device: Option<(u16, Device<S>)>,
+
+    current_class: Option<(u8, Class<S>)>,
+    current_subclass: Option<(u8, SubClass<S>)>,
+
+    vendors: BTreeMap<u16, Vendor<S>>,
+    classes: BTreeMap<u8, Class<S>>,
+}
+
+impl<S> Builder<S> {
+    pub fn new() -> Self {
+        Self {
+            current_vendor: None,
+            current_device: None,
+            current_class: None,
+            current_subclass: None,
+            vendors: BTreeMap::new(),
+            classes: BTreeMap::new(),
+        }
+    }
+
+    pub fn push<'e>(&mut self, event: Event<'e>)
+    where
+        S: From<&'e str>,
+    {
+        match event {
+            Event::Vendor { id, name } => {
+                // The vendor section is complete so it needs to be pushed to the main list
+                self.finish_vendor();
+
+                let vendor = Vendor {
+                    name: name.into(),
+                    devices: BTreeMap::new(),
+                };
+                self.current_vendor = Some((id, vendor));
+            }
+            Event::Device { id, name } => {
+                // Device section is over, write to vendor
+                self.finish_device();
+
+                let device = Device {
+                    name: name.into(),
+                    subdevices: BTreeMap::new(),
+                };
+                self.current_device = Some((id, device));
+            }
+            Event::Subdevice {
+                subvendor,
+                subdevice,
+                subsystem_name,
+            } => {
+                // The parser rejects subdevices that are not nested under a device
+                if let Some((_, device)) = self.current_device.as_mut() {
+                    let subdevice_id = SubDeviceId {
+                        subvendor,
+                        subdevice,
+                    };
+                    device
+                        .subdevices
+                        .insert(subdevice_id, subsystem_name.into());
+                }
+            }
+            Event::Class { id, name } => {
+                self.finish_class();
+
+                let class = Class {
+                    name: name.into(),
+                    subclasses: BTreeMap::new(),
+                };
+                self.current_class = Some((id, class));
+            }
+            Event::SubClass { id, name } => {
+                self.finish_subclass();
+
+                let subclass = SubClass {
+                    name: name.into(),
+                    prog_ifs: BTreeMap::new(),
+                };
+                self.current_subclass = Some((id, subclass));
+            }
+            Event::ProgIf { id, name } => {
+                if let Some((_, subclass)) = self.current_subclass.as_mut() {
+                    subclass.prog_ifs.insert(id, name.into());
+                }
+            }
+        }
+    }
+
+    pub fn finish(mut self, metadata: Metadata) -> Database<S> {
+        // Finish writing the last vendor and class
+        self.finish_vendor();
+        self.finish_class();
+
+        Database {
+            vendors: self.vendors,
+            classes: self.classes,
+            metadata,
+            #[cfg(feature = "std")]
+            overlays: OverlaySources::default(),
+        }
+    }
+
+    fn finish_device(&mut self) {
+        if let (Some((device_id, device)), Some((_, vendor))) =
+            (self.current_device.take(), self.current_vendor.as_mut())
+        {
+            vendor.devices.insert(device_id, device);
+        }
+    }
+
+    fn finish_vendor(&mut self) {
+        self.finish_device();
+        if let Some((vendor_id, vendor)) = self.current_vendor.take() {
+            self.vendors.insert(vendor_id, vendor);
+        }
+    }
+
+    fn finish_subclass(&mut self) {
+        if let (Some((subclass_id, subclass)), Some((_, class))) =
+            (self.current_subclass.take(), self.current_class.as_mut())
+        {
+            class.subclasses.insert(subclass_id, subclass);
+        }
+    }
+
+    fn finish_class(&mut self) {
+        self.finish_subclass();
+        if let Some((class_id, class)) = self.current_class.take() {
+            self.classes.insert(class_id, class);
+        }
+    }
+}
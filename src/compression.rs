@@ -0,0 +1,186 @@
+//! Transparent decompression of database files, detected by their magic bytes
+use crate::error::Error;
+use std::{
+    fmt::{self, Display},
+    io::{self, Cursor, Read},
+};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const MAGIC_LEN: usize = 6;
+
+/// Compression formats that database files can be stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Xz,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Formats that this build can decompress
+    pub(crate) const ENABLED: &'static [Compression] = &[
+        #[cfg(feature = "gzip")]
+        Compression::Gzip,
+        #[cfg(feature = "xz")]
+        Compression::Xz,
+        #[cfg(feature = "zstd")]
+        Compression::Zstd,
+        #[cfg(feature = "bzip2")]
+        Compression::Bzip2,
+    ];
+
+    /// Detect the format from the first bytes of a file
+    #[must_use]
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else if header.starts_with(XZ_MAGIC) {
+            Some(Self::Xz)
+        } else if header.starts_with(ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else if header.starts_with(BZIP2_MAGIC) {
+            Some(Self::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    /// The usual file extension, including the leading dot
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gzip => ".gz",
+            Self::Xz => ".xz",
+            Self::Zstd => ".zst",
+            Self::Bzip2 => ".bz2",
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Gzip => "gzip",
+            Self::Xz => "xz",
+            Self::Zstd => "zstd",
+            Self::Bzip2 => "bzip2",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Wrap the reader in a decoder if its contents are compressed
+pub(crate) fn decompress<'a, R: Read + 'a>(reader: R) -> Result<Box<dyn Read + 'a>, Error> {
+    decompress_limited(reader, u64::MAX)
+}
+
+/// Like [`decompress`], but reading fails once more than `limit` bytes were decompressed
+pub(crate) fn decompress_limited<'a, R: Read + 'a>(
+    reader: R,
+    limit: u64,
+) -> Result<Box<dyn Read + 'a>, Error> {
+    let reader = decoder(reader, limit)?;
+    Ok(Box::new(SizeLimited {
+        inner: reader,
+        remaining: limit,
+    }))
+}
+
+#[cfg_attr(not(feature = "xz"), allow(unused_variables))]
+fn decoder<'a, R: Read + 'a>(mut reader: R, limit: u64) -> Result<Box<dyn Read + 'a>, Error> {
+    let mut magic = [0; MAGIC_LEN];
+    let len = read_prefix(&mut reader, &mut magic)?;
+    let reader = Cursor::new(magic).take(len as u64).chain(reader);
+
+    match Compression::detect(&magic[..len]) {
+        None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Some(Compression::Gzip) => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "xz")]
+        Some(Compression::Xz) => {
+            // lzma-rs has no streaming reader, but the database is small enough to be decoded at once
+            let mut output = SizeLimited {
+                inner: Vec::new(),
+                remaining: limit,
+            };
+            lzma_rs::xz_decompress(&mut io::BufReader::new(reader), &mut output).map_err(
+                |err| match err {
+                    lzma_rs::error::Error::IoError(err) => err,
+                    err => invalid_data(format!("{err:?}")),
+                },
+            )?;
+            Ok(Box::new(Cursor::new(output.inner)))
+        }
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstd) => {
+            let decoder = ruzstd::decoding::StreamingDecoder::new(reader)
+                .map_err(|err| invalid_data(err.to_string()))?;
+            Ok(Box::new(decoder))
+        }
+        #[cfg(feature = "bzip2")]
+        Some(Compression::Bzip2) => Ok(Box::new(bzip2::read::MultiBzDecoder::new(reader))),
+        #[allow(unreachable_patterns)]
+        Some(compression) => Err(Error::UnsupportedCompression(compression)),
+    }
+}
+
+/// Fails once more than the given number of bytes were read or written,
+/// unlike [`Read::take`] which stops silently
+struct SizeLimited<T> {
+    inner: T,
+    remaining: u64,
+}
+
+impl<T> SizeLimited<T> {
+    fn consume(&mut self, len: usize) -> io::Result<usize> {
+        self.remaining = self.remaining.checked_sub(len as u64).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the decompressed database exceeds the size limit",
+            )
+        })?;
+        Ok(len)
+    }
+}
+
+impl<R: Read> Read for SizeLimited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.consume(len)
+    }
+}
+
+#[cfg(feature = "xz")]
+impl<W: io::Write> io::Write for SizeLimited<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.consume(buf.len())?;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Fill the buffer as far as possible, returning the amount of bytes read
+fn read_prefix<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(any(feature = "xz", feature = "zstd"))]
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,198 @@
+//! Descriptions of devices in the format that `lspci` prints, including its fallbacks for unknown ids.
+//!
+//! ```rust
+//! use pciid_parser::{display::{self, Options}, Database};
+//!
+//! let data = "1002  Advanced Micro Devices, Inc. [AMD/ATI]\n\t67df  Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]\n";
+//! let db: Database = data.parse().unwrap();
+//! let info = db.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+//! assert_eq!(
+//!     display::device(0x1002, 0x67df, &info, Options::default()),
+//!     "Advanced Micro Devices, Inc. [AMD/ATI] Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]"
+//! );
+//!
+//! let info = db.get_device_info(0x1002, 0x1234, 0, 0);
+//! assert_eq!(
+//!     display::device(0x1002, 0x1234, &info, Options::numeric()),
+//!     "Advanced Micro Devices, Inc. [AMD/ATI] Device [1002:1234]"
+//! );
+//! ```
+use crate::schema::{ClassInfo, DeviceInfo};
+#[cfg(feature = "std")]
+use crate::sysfs::PciDevice;
+use alloc::{format, string::String};
+#[cfg(feature = "std")]
+use core::fmt::Write;
+
+/// How much detail to include
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Add the ids in brackets after the names, like `lspci -nn`
+    pub numeric: bool,
+    /// Add the programming interface to device lines, like `lspci -v`
+    pub verbose: bool,
+}
+
+impl Options {
+    /// Options matching `lspci -nn`
+    #[must_use]
+    pub fn numeric() -> Self {
+        Self {
+            numeric: true,
+            verbose: false,
+        }
+    }
+}
+
+/// Describe a class from its 24-bit class code, such as `VGA compatible controller`.
+/// Falls back to the class name when the subclass is unknown, or to `Class 0300`.
+#[must_use]
+pub fn class(class_code: u32, info: &ClassInfo<'_>, options: Options) -> String {
+    let code = class_code >> 8;
+
+    match (info.subclass_name, info.class_name) {
+        (Some(name), _) if options.numeric => format!("{name} [{code:04x}]"),
+        (Some(name), _) => name.into(),
+        (None, Some(name)) => format!("{name} [{code:04x}]"),
+        (None, None) if options.numeric => format!("Class [{code:04x}]"),
+        (None, None) => format!("Class {code:04x}"),
+    }
+}
+
+/// Describe a device with its vendor, such as `Advanced Micro Devices, Inc. [AMD/ATI] Ellesmere [...]`.
+/// Falls back to `Device 67df` for unknown devices and to `Device 1002:67df` for unknown vendors.
+#[must_use]
+pub fn device(vendor_id: u16, device_id: u16, info: &DeviceInfo<'_>, options: Options) -> String {
+    name_pair(
+        info.vendor_name,
+        info.device_name,
+        vendor_id,
+        device_id,
+        options,
+    )
+}
+
+/// Describe the subsystem of a device, such as `Sapphire Technology Limited Radeon RX 580 Pulse 4GB`.
+///
+/// A subsystem with the same ids as the device itself is described with the name of the device.
+/// Unknown subsystems fall back in the same way as [`device`].
+#[must_use]
+pub fn subsystem(
+    vendor_id: u16,
+    device_id: u16,
+    subsystem_vendor_id: u16,
+    subsystem_device_id: u16,
+    info: &DeviceInfo<'_>,
+    options: Options,
+) -> String {
+    let subdevice_name = info.subdevice_name.or_else(|| {
+        (subsystem_vendor_id == vendor_id && subsystem_device_id == device_id)
+            .then_some(info.device_name)
+            .flatten()
+    });
+
+    name_pair(
+        info.subvendor_name,
+        subdevice_name,
+        subsystem_vendor_id,
+        subsystem_device_id,
+        options,
+    )
+}
+
+/// Describe the programming interface from a 24-bit class code, such as `prog-if 00 [VGA controller]`.
+/// Returns `None` when the interface is zero and has no name, in which case `lspci -v` omits it.
+#[must_use]
+pub fn prog_if(class_code: u32, info: &ClassInfo<'_>) -> Option<String> {
+    let prog_if = class_code & 0xff;
+
+    match info.prog_if_name {
+        Some(name) => Some(format!("prog-if {prog_if:02x} [{name}]")),
+        None if prog_if != 0 => Some(format!("prog-if {prog_if:02x}")),
+        None => None,
+    }
+}
+
+/// The line that `lspci` prints for a device, such as
+/// `03:00.0 VGA compatible controller: Advanced Micro Devices, Inc. [AMD/ATI] Ellesmere [...] (rev e7)`.
+///
+/// The domain is only included when it is not zero.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn line(device: &PciDevice<'_>, options: Options) -> String {
+    let address = device.address;
+    let mut line = if address.domain == 0 {
+        format!(
+            "{:02x}:{:02x}.{:x}",
+            address.bus, address.device, address.function
+        )
+    } else {
+        address.to_string()
+    };
+
+    let _ = write!(
+        line,
+        " {}: {}",
+        class(device.class_code, &device.class_info, options),
+        self::device(
+            device.vendor_id,
+            device.device_id,
+            &device.device_info,
+            options
+        )
+    );
+
+    if device.revision != 0 {
+        let _ = write!(line, " (rev {:02x})", device.revision);
+    }
+
+    if options.verbose {
+        if let Some(prog_if) = prog_if(device.class_code, &device.class_info) {
+            let _ = write!(line, " ({prog_if})");
+        }
+    }
+
+    line
+}
+
+/// The `Subsystem:` line that `lspci -v` prints below a device.
+/// Returns `None` for devices without a subsystem.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn subsystem_line(device: &PciDevice<'_>, options: Options) -> Option<String> {
+    if matches!(device.subsystem_vendor_id, 0 | 0xffff) {
+        return None;
+    }
+
+    let name = subsystem(
+        device.vendor_id,
+        device.device_id,
+        device.subsystem_vendor_id,
+        device.subsystem_device_id,
+        &device.device_info,
+        options,
+    );
+    Some(format!("Subsystem: {name}"))
+}
+
+/// Combine a vendor and a device name in the way `lspci` does, with numeric fallbacks
+fn name_pair(
+    vendor_name: Option<&str>,
+    device_name: Option<&str>,
+    vendor_id: u16,
+    device_id: u16,
+    options: Options,
+) -> String {
+    match (vendor_name, device_name) {
+        (Some(vendor), Some(device)) if options.numeric => {
+            format!("{vendor} {device} [{vendor_id:04x}:{device_id:04x}]")
+        }
+        (Some(vendor), Some(device)) => format!("{vendor} {device}"),
+        (Some(vendor), None) if options.numeric => {
+            format!("{vendor} Device [{vendor_id:04x}:{device_id:04x}]")
+        }
+        (Some(vendor), None) => format!("{vendor} Device {device_id:04x}"),
+        (None, _) if options.numeric => format!("Device [{vendor_id:04x}:{device_id:04x}]"),
+        (None, _) => format!("Device {vendor_id:04x}:{device_id:04x}"),
+    }
+}
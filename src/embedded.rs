@@ -0,0 +1,223 @@
+//! A database that is parsed at build time and compiled into the binary.
+//!
+//! The bundled `pci.ids` is used unless the `PCIIDS_EMBED_PATH` environment variable points to another file
+//! when the crate is built. Lookups binary search sorted static tables, so nothing is parsed or
+//! allocated at runtime.
+//!
+//! ```rust
+//! use pciid_parser::embedded::EMBEDDED;
+//!
+//! let info = EMBEDDED.get_device_info(0x1002, 0x67df, 0, 0);
+//! assert_eq!(info.vendor_name, Some("Advanced Micro Devices, Inc. [AMD/ATI]"));
+//! ```
+use crate::{
+    modalias::{Modalias, ModaliasInfo},
+    schema::{ClassInfo, DeviceInfo, EntryId, Metadata, Timestamp, Version},
+};
+
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+/// A database made of static tables. Every table is sorted by id.
+#[derive(Debug)]
+pub struct StaticDatabase {
+    pub vendors: &'static [StaticVendor],
+    pub classes: &'static [StaticClass],
+    pub metadata: Metadata,
+}
+
+#[derive(Debug)]
+pub struct StaticVendor {
+    pub id: u16,
+    pub name: &'static str,
+    pub devices: &'static [StaticDevice],
+}
+
+#[derive(Debug)]
+pub struct StaticDevice {
+    pub id: u16,
+    pub name: &'static str,
+    pub subdevices: &'static [StaticSubdevice],
+}
+
+#[derive(Debug)]
+pub struct StaticSubdevice {
+    pub subvendor: u16,
+    pub subdevice: u16,
+    pub name: &'static str,
+}
+
+#[derive(Debug)]
+pub struct StaticClass {
+    pub id: u8,
+    pub name: &'static str,
+    pub subclasses: &'static [StaticSubClass],
+}
+
+#[derive(Debug)]
+pub struct StaticSubClass {
+    pub id: u8,
+    pub name: &'static str,
+    pub prog_ifs: &'static [StaticProgIf],
+}
+
+#[derive(Debug)]
+pub struct StaticProgIf {
+    pub id: u8,
+    pub name: &'static str,
+}
+
+impl StaticDatabase {
+    #[must_use]
+    pub fn vendor(&self, vendor_id: u16) -> Option<&'static StaticVendor> {
+        find(self.vendors, &vendor_id, |vendor| vendor.id)
+    }
+
+    #[must_use]
+    pub fn class(&self, class_id: u8) -> Option<&'static StaticClass> {
+        find(self.classes, &class_id, |class| class.id)
+    }
+
+    #[must_use]
+    pub fn get_device_info(
+        &self,
+        vendor_id: u16,
+        model_id: u16,
+        subsys_vendor_id: u16,
+        subsys_model_id: u16,
+    ) -> DeviceInfo<'static> {
+        let mut info = DeviceInfo::default();
+
+        if let Some(vendor) = self.vendor(vendor_id) {
+            info.vendor_name = Some(vendor.name);
+
+            if let Some(device) = vendor.device(model_id) {
+                info.device_name = Some(device.name);
+                info.subvendor_name = self
+                    .vendor(subsys_vendor_id)
+                    .map(|subvendor| subvendor.name);
+                info.subdevice_name = device
+                    .subdevice(subsys_vendor_id, subsys_model_id)
+                    .map(|subdevice| subdevice.name);
+            }
+        }
+
+        info
+    }
+
+    #[must_use]
+    pub fn get_class_info(
+        &self,
+        class_id: u8,
+        subclass_id: u8,
+        prog_if_id: u8,
+    ) -> ClassInfo<'static> {
+        let mut info = ClassInfo::default();
+
+        if let Some(class) = self.class(class_id) {
+            info.class_name = Some(class.name);
+
+            if let Some(subclass) = class.subclass(subclass_id) {
+                info.subclass_name = Some(subclass.name);
+                info.prog_if_name = subclass.prog_if(prog_if_id).map(|prog_if| prog_if.name);
+            }
+        }
+
+        info
+    }
+
+    /// Look up the class names from a packed 24-bit class code.
+    /// All names are empty when the code doesn't fit in 24 bits.
+    #[must_use]
+    pub fn get_class_info_by_code(&self, class_code: u32) -> ClassInfo<'static> {
+        let [0, class_id, subclass_id, prog_if_id] = class_code.to_be_bytes() else {
+            return ClassInfo::default();
+        };
+        self.get_class_info(class_id, subclass_id, prog_if_id)
+    }
+
+    /// Look up the device and class names described by a modalias
+    #[must_use]
+    pub fn lookup_modalias(&self, modalias: &Modalias) -> ModaliasInfo<'static> {
+        ModaliasInfo {
+            device_info: self.get_device_info(
+                modalias.vendor_id,
+                modalias.device_id,
+                modalias.subsystem_vendor_id,
+                modalias.subsystem_device_id,
+            ),
+            class_info: self.get_class_info(modalias.class, modalias.subclass, modalias.prog_if),
+        }
+    }
+
+    /// Get the name of any entry in the database
+    #[must_use]
+    pub fn get_name(&self, entry: EntryId) -> Option<&'static str> {
+        let name = match entry {
+            EntryId::Vendor(vendor) => self.vendor(vendor)?.name,
+            EntryId::Device { vendor, device } => self.vendor(vendor)?.device(device)?.name,
+            EntryId::Subdevice {
+                vendor,
+                device,
+                subvendor,
+                subdevice,
+            } => {
+                self.vendor(vendor)?
+                    .device(device)?
+                    .subdevice(subvendor, subdevice)?
+                    .name
+            }
+            EntryId::Class(class) => self.class(class)?.name,
+            EntryId::SubClass { class, subclass } => self.class(class)?.subclass(subclass)?.name,
+            EntryId::ProgIf {
+                class,
+                subclass,
+                prog_if,
+            } => {
+                self.class(class)?
+                    .subclass(subclass)?
+                    .prog_if(prog_if)?
+                    .name
+            }
+        };
+        Some(name)
+    }
+}
+
+impl StaticVendor {
+    #[must_use]
+    pub fn device(&self, device_id: u16) -> Option<&'static StaticDevice> {
+        find(self.devices, &device_id, |device| device.id)
+    }
+}
+
+impl StaticDevice {
+    #[must_use]
+    pub fn subdevice(&self, subvendor: u16, subdevice: u16) -> Option<&'static StaticSubdevice> {
+        find(self.subdevices, &(subvendor, subdevice), |entry| {
+            (entry.subvendor, entry.subdevice)
+        })
+    }
+}
+
+impl StaticClass {
+    #[must_use]
+    pub fn subclass(&self, subclass_id: u8) -> Option<&'static StaticSubClass> {
+        find(self.subclasses, &subclass_id, |subclass| subclass.id)
+    }
+}
+
+impl StaticSubClass {
+    #[must_use]
+    pub fn prog_if(&self, prog_if_id: u8) -> Option<&'static StaticProgIf> {
+        find(self.prog_ifs, &prog_if_id, |prog_if| prog_if.id)
+    }
+}
+
+fn find<T, K: Ord>(
+    entries: &'static [T],
+    key: &K,
+    get_key: impl FnMut(&T) -> K,
+) -> Option<&'static T> {
+    let index = entries.binary_search_by_key(key, get_key).ok()?;
+    Some(&entries[index])
+}
@@ -1,20 +1,49 @@
-use std::fmt::Display;
+mod parse;
+
+#[cfg(feature = "std")]
+use crate::compression::Compression;
+#[cfg(feature = "online")]
+use alloc::boxed::Box;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt::Display;
+pub use parse::{ErrorKind, ParseError};
 
 #[derive(Debug)]
 pub enum Error {
-    FileNotFound,
-    Parse(String),
+    /// None of the database files exist, with every path that was checked
+    #[cfg(feature = "std")]
+    FileNotFound(Vec<std::path::PathBuf>),
+    Parse(ParseError),
+    InvalidModalias(String),
+    /// The input is not valid UTF-8
+    InvalidUtf8(core::str::Utf8Error),
+    /// The file is compressed with a format whose feature is not enabled
+    #[cfg(feature = "std")]
+    UnsupportedCompression(Compression),
+    /// The binary snapshot is not valid, with the reason
+    #[cfg(feature = "std")]
+    InvalidSnapshot(&'static str),
+    #[cfg(feature = "std")]
     Io(std::io::Error),
     #[cfg(feature = "online")]
     Request(Box<ureq::Error>),
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
         Self::Io(error)
     }
 }
 
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
 #[cfg(feature = "online")]
 impl From<ureq::Error> for Error {
     fn from(error: ureq::Error) -> Self {
@@ -23,10 +52,30 @@ impl From<ureq::Error> for Error {
 }
 
 impl Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            Error::FileNotFound => write!(f, "file not found"),
+            #[cfg(feature = "std")]
+            Error::FileNotFound(paths) => {
+                write!(f, "file not found, checked ")?;
+                for (i, path) in paths.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", path.display())?;
+                }
+                Ok(())
+            }
             Error::Parse(err) => write!(f, "parsing error: {err}"),
+            Error::InvalidModalias(modalias) => write!(f, "invalid modalias {modalias:?}"),
+            Error::InvalidUtf8(err) => write!(f, "invalid utf-8: {err}"),
+            #[cfg(feature = "std")]
+            Error::UnsupportedCompression(compression) => write!(
+                f,
+                "{compression} compressed database, but the `{compression}` feature is not enabled"
+            ),
+            #[cfg(feature = "std")]
+            Error::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {reason}"),
+            #[cfg(feature = "std")]
             Error::Io(err) => write!(f, "io error: {err}"),
             #[cfg(feature = "online")]
             Error::Request(err) => write!(f, "network request error: {err}"),
@@ -34,36 +83,20 @@ impl Display for Error {
     }
 }
 
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
-            Error::FileNotFound => None,
-            Error::Parse(_) => None,
+            Error::InvalidModalias(_) => None,
+            #[cfg(feature = "std")]
+            Error::FileNotFound(_)
+            | Error::UnsupportedCompression(_)
+            | Error::InvalidSnapshot(_) => None,
+            Error::Parse(err) => Some(err),
+            Error::InvalidUtf8(err) => Some(err),
+            #[cfg(feature = "std")]
             Error::Io(err) => Some(err),
             #[cfg(feature = "online")]
             Error::Request(err) => Some(err),
         }
     }
 }
-
-impl Error {
-    pub(crate) fn no_current_vendor() -> Error {
-        Error::Parse("trying to add a device without a vendor".to_owned())
-    }
-
-    pub(crate) fn no_current_device() -> Error {
-        Error::Parse("trying to add a subdevice without a device".to_owned())
-    }
-
-    pub(crate) fn no_current_class() -> Error {
-        Error::Parse("trying to add a subclass without a class".to_owned())
-    }
-
-    pub(crate) fn no_current_subclass() -> Error {
-        Error::Parse("trying to add a programming interface without a subclass".to_owned())
-    }
-
-    pub(crate) fn invalid_int(value: &str) -> Error {
-        Error::Parse(format!("Could not parse {value} as integer"))
-    }
-}
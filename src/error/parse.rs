@@ -0,0 +1,57 @@
+//! Errors in the database format, kept free of optional dependencies so that the build script can use them
+use alloc::string::String;
+use core::fmt::Display;
+
+/// A malformed line in the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Byte offset of the start of the line
+    pub offset: u64,
+    /// The offending line, without the line terminator
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The line has no double space between the id and the name
+    MissingDelimiter,
+    /// The id is not a valid hex number
+    InvalidHexId,
+    /// A device line appears before any vendor
+    OrphanDevice,
+    /// A subdevice line appears before any device
+    OrphanSubdevice,
+    /// A subclass line appears before any class
+    OrphanSubclass,
+    /// A programming interface line appears before any subclass
+    OrphanProgIf,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} in line {} (byte {}): {:?}",
+            self.kind, self.line, self.offset, self.content
+        )
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            ErrorKind::MissingDelimiter => "missing delimiter",
+            ErrorKind::InvalidHexId => "invalid hex id",
+            ErrorKind::OrphanDevice => "device without a vendor",
+            ErrorKind::OrphanSubdevice => "subdevice without a device",
+            ErrorKind::OrphanSubclass => "subclass without a class",
+            ErrorKind::OrphanProgIf => "programming interface without a subclass",
+        };
+        f.write_str(message)
+    }
+}
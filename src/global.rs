@@ -0,0 +1,47 @@
+//! A database that is shared by the whole process, so that the file is only parsed once
+use crate::{Database, Error};
+use std::sync::OnceLock;
+
+static GLOBAL: OnceLock<Database> = OnceLock::new();
+
+/// Get the shared database, reading it from the default location on first use.
+///
+/// # Errors
+/// Returns an error when the database can't be read. Nothing is stored then, so the next call tries again.
+pub fn try_global() -> Result<&'static Database, Error> {
+    if let Some(db) = GLOBAL.get() {
+        return Ok(db);
+    }
+
+    // Threads that race here parse the file separately, but all of them get the database that was stored first
+    let db = Database::read()?;
+    Ok(GLOBAL.get_or_init(|| db))
+}
+
+/// Get the shared database, reading it from the default location on first use.
+///
+/// ```rust,no_run
+/// let db = pciid_parser::global();
+/// let info = db.get_device_info(0x1002, 0x67df, 0, 0);
+/// ```
+///
+/// # Panics
+/// Panics when the database can't be read, see [`try_global`] for handling the error
+#[must_use]
+pub fn global() -> &'static Database {
+    match try_global() {
+        Ok(db) => db,
+        Err(err) => panic!("could not read the PCI ID database: {err}"),
+    }
+}
+
+/// Use the given database as the shared one instead of reading it from the default location,
+/// such as a test database or one that was fetched online.
+///
+/// # Errors
+/// Gives the database back when the shared one has already been initialized
+// Same signature as `OnceLock::set`, the database is only moved back in the rare case that it's not used
+#[allow(clippy::result_large_err)]
+pub fn init_with(db: Database) -> Result<(), Database> {
+    GLOBAL.set(db)
+}
@@ -1,45 +1,110 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(clippy::pedantic)]
-#![doc = include_str!("../README.md")]
+// The examples in the readme read the system database
+#![cfg_attr(feature = "std", doc = include_str!("../README.md"))]
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+mod builder;
+#[cfg(feature = "std")]
+pub mod compression;
+pub mod display;
+#[cfg(feature = "embedded")]
+pub mod embedded;
 mod error;
-mod parser;
+#[cfg(feature = "std")]
+mod global;
+#[cfg(feature = "std")]
+pub mod locator;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod modalias;
+pub mod name;
+#[cfg(feature = "online")]
+pub mod online;
+#[cfg(feature = "std")]
+pub mod overlay;
+pub mod parser;
 pub mod schema;
-
-use crate::parser::Parser;
-use error::Error;
-use parser::Event;
-use schema::{Class, Device, DeviceInfo, SubClass, SubDeviceId, Vendor};
+pub mod search;
+#[cfg(feature = "std")]
+pub mod snapshot;
+#[cfg(feature = "std")]
+pub mod sysfs;
+#[cfg(feature = "std")]
+mod writer;
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use builder::Builder;
+use core::{cmp::Ordering, str::FromStr};
+pub use error::{Error, ErrorKind, ParseError};
+#[cfg(feature = "std")]
+pub use global::{global, init_with, try_global};
+#[cfg(feature = "std")]
+use overlay::OverlaySources;
+use parser::StrParser;
+#[cfg(feature = "std")]
+use parser::{Event, Parser};
+pub use parser::{ParseOptions, Warning};
+use schema::{Class, ClassInfo, DeviceInfo, EntryId, Metadata, SubDeviceId, Vendor};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use std::{
-    collections::HashMap,
     fs::File,
-    io::{BufReader, Read},
+    io::{BufReader, Read, Write},
     path::{Path, PathBuf},
 };
 
+/// Known distribution paths, checked after the ones from the environment.
+/// See [`locator`] for the full search order.
+#[cfg(feature = "std")]
 const DB_PATHS: &[&str] = &[
     "/usr/share/hwdata/pci.ids",
     "/usr/share/misc/pci.ids",
     "/run/current-system/sw/share/pci.ids", // NixOS
     "@hwdata@/share/hwdata/pci.ids",
 ];
-#[cfg(feature = "online")]
-const URL: &str = "https://pci-ids.ucw.cz/v2.2/pci.ids";
 
 #[derive(Debug)]
 pub enum VendorDataError {
     MissingIdsFile,
 }
 
-#[derive(Debug)]
+/// A parsed PCI ID database.
+///
+/// Names are owned by default. See [`BorrowedDatabase`] for a database that borrows them from its input.
+#[derive(Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Database {
-    pub vendors: HashMap<u16, Vendor>,
-    pub classes: HashMap<u8, Class>,
+// `open_mmap` is unsafe because of the file it maps, not because of any invariant of the fields
+#[cfg_attr(feature = "mmap", allow(clippy::unsafe_derive_deserialize))]
+pub struct Database<S = String> {
+    pub vendors: BTreeMap<u16, Vendor<S>>,
+    pub classes: BTreeMap<u8, Class<S>>,
+    pub metadata: Metadata,
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    overlays: OverlaySources,
 }
 
+/// A database whose names are slices of an in-memory `pci.ids` file, such as an `include_str!`.
+/// It offers the same lookups as [`Database`], but parsing it doesn't allocate a string for every entry.
+///
+/// ```rust
+/// use pciid_parser::BorrowedDatabase;
+///
+/// let data = "1002  Advanced Micro Devices, Inc. [AMD/ATI]\n\t67df  Ellesmere\n";
+/// let db = BorrowedDatabase::parse_str(data).unwrap();
+/// assert_eq!(db.get_device_info(0x1002, 0x67df, 0, 0).device_name, Some("Ellesmere"));
+/// ```
+pub type BorrowedDatabase<'a> = Database<&'a str>;
+
+#[cfg(feature = "std")]
 impl Database {
-    /// Attempt to read the database from a list of known file paths
+    /// Attempt to read the database from a list of known file paths, which can be overridden with environment variables.
+    /// Compressed variants of the paths (such as `pci.ids.gz`) are also checked when the matching feature is enabled.
+    /// See [`locator::DatabaseLocator`] for the search order and for finding out which file was read.
     ///
     /// # Errors
     /// Returns an error when either no file could be found or the parsing fails.
@@ -48,189 +113,148 @@ impl Database {
         Self::parse_db(file)
     }
 
-    /// Read the database from a given path
+    /// Read the database from a given path.
+    /// Compressed files are detected automatically when the matching feature is enabled.
     ///
     /// # Errors
-    /// Returns an error when the file can't be read or when parsing fails
+    /// Returns an error when the file can't be read or decompressed or when parsing fails
     pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let file = File::open(path)?;
-        Self::parse_db(file)
+        Self::parse_db(compression::decompress(file)?)
     }
 
-    /// Fetch a database from an online source
+    /// Fetch a database from an online source.
+    /// See [`Database::get_online_with`] for mirrors, timeouts and caching the downloaded file.
     ///
     /// # Errors
     /// Returns an error when the database either can't be fetched or parsed
     #[cfg(feature = "online")]
     pub fn get_online() -> Result<Self, Error> {
-        let response = ureq::get(URL).call()?;
+        online::OnlineSource::new().fetch()
+    }
 
-        Self::parse_db(response.into_body().into_reader())
+    /// Fetch a database from a configured online source
+    ///
+    /// # Errors
+    /// Returns an error when the database can't be fetched from any of the URLs or parsed
+    #[cfg(feature = "online")]
+    pub fn get_online_with(source: &online::OnlineSource) -> Result<Self, Error> {
+        source.fetch()
     }
 
     /// Parse a database from the given reader
     ///
     /// # Errors
     /// Returns an error whenever there's a parsing error
-    #[allow(clippy::too_many_lines)] // todo
     pub fn parse_db<R: Read>(reader: R) -> Result<Self, Error> {
-        let reader = BufReader::new(reader);
-        let mut parser = Parser::new(reader);
-
-        let mut current_vendor: Option<(u16, Vendor)> = None;
-        let mut current_device: Option<(u16, Device)> = None;
-
-        let mut current_class: Option<(u8, Class)> = None;
-        let mut current_subclass: Option<(u8, SubClass)> = None;
+        let (db, _) = Self::parse_db_with_options(reader, ParseOptions::default())?;
+        Ok(db)
+    }
 
-        let mut vendors: HashMap<u16, Vendor> = HashMap::with_capacity(2500);
-        let mut classes: HashMap<u8, Class> = HashMap::with_capacity(200);
+    /// Parse a database from the given reader with custom options.
+    /// In lenient mode the lines that were skipped are returned alongside the database.
+    ///
+    /// # Errors
+    /// Returns an error when reading fails or, in strict mode, whenever there's a parsing error
+    pub fn parse_db_with_options<R: Read>(
+        reader: R,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Warning>), Error> {
+        let reader = BufReader::new(reader);
+        let mut parser = Parser::with_options(reader, options);
+        let mut builder = Builder::new();
 
         while let Some(event) = parser.next_event()? {
-            match event {
-                Event::Vendor { id, name } => {
-                    // The vendor section is complete so it needs to be pushed to the main list
-                    if let Some((device_id, device)) = current_device.take() {
-                        let (_, vendor) = current_vendor
-                            .as_mut()
-                            .ok_or_else(Error::no_current_vendor)?;
-                        vendor.devices.insert(device_id, device);
-                    }
-                    if let Some((vendor_id, vendor)) = current_vendor.take() {
-                        vendors.insert(vendor_id, vendor);
-                    }
-
-                    let vendor = Vendor {
-                        name: name.to_owned(),
-                        devices: HashMap::new(),
-                    };
-                    current_vendor = Some((
-                        u16::from_str_radix(id, 16).map_err(|_| Error::invalid_int(id))?,
-                        vendor,
-                    ));
-                }
-                Event::Device { id, name } => {
-                    // Device section is over, write to vendor
-                    if let Some((device_id, device)) = current_device.take() {
-                        let (_, current_vendor) = current_vendor
-                            .as_mut()
-                            .ok_or_else(Error::no_current_vendor)?;
-
-                        current_vendor.devices.insert(device_id, device);
-                    }
+            builder.push(event);
+        }
 
-                    let device = Device {
-                        name: name.to_owned(),
-                        subdevices: HashMap::new(),
-                    };
+        let db = builder.finish(parser.metadata().clone());
+        Ok((db, parser.take_warnings()))
+    }
 
-                    current_device = Some((
-                        u16::from_str_radix(id, 16).map_err(|_| Error::invalid_int(id))?,
-                        device,
-                    ));
-                }
-                Event::Subdevice {
-                    subvendor,
-                    subdevice,
-                    subsystem_name,
-                } => {
-                    let (_, current_device) = current_device
-                        .as_mut()
-                        .ok_or_else(Error::no_current_device)?;
-
-                    let subdevice_id = SubDeviceId {
-                        subvendor: u16::from_str_radix(subvendor, 16)
-                            .map_err(|_| Error::invalid_int(subvendor))?,
-                        subdevice: u16::from_str_radix(subdevice, 16)
-                            .map_err(|_| Error::invalid_int(subdevice))?,
-                    };
-                    current_device
-                        .subdevices
-                        .insert(subdevice_id, subsystem_name.to_owned());
-                }
-                Event::Class { id, name } => {
-                    if let Some((subclass_id, subclass)) = current_subclass.take() {
-                        let (_, class) =
-                            current_class.as_mut().ok_or_else(Error::no_current_class)?;
+    /// Find the database file that [`Database::read`] uses: the first one that exists
+    /// in the known locations, preferring uncompressed files in each location.
+    ///
+    /// # Errors
+    /// Returns [`Error::FileNotFound`] with every path that was checked when none of the files exist
+    pub fn default_path() -> Result<PathBuf, Error> {
+        locator::DatabaseLocator::new().locate()
+    }
 
-                        class.subclasses.insert(subclass_id, subclass);
-                    }
-                    if let Some((class_id, class)) = current_class.take() {
-                        classes.insert(class_id, class);
-                    }
+    /// Open the default database file
+    fn open_file() -> Result<Box<dyn Read>, Error> {
+        let path = Self::default_path()?;
+        compression::decompress(File::open(path)?)
+    }
+}
 
-                    let class = Class {
-                        name: name.to_owned(),
-                        subclasses: HashMap::new(),
-                    };
-                    current_class = Some((
-                        u8::from_str_radix(id, 16).map_err(|_| Error::invalid_int(id))?,
-                        class,
-                    ));
-                }
-                Event::SubClass { id, name } => {
-                    if let Some((subclass_id, subclass)) = current_subclass {
-                        let (_, class) =
-                            current_class.as_mut().ok_or_else(Error::no_current_class)?;
+/// Parse an owned database from a string, for when there is no reader to parse from
+impl FromStr for Database {
+    type Err = Error;
 
-                        class.subclasses.insert(subclass_id, subclass);
-                    }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = StrParser::new(s);
+        let mut builder = Builder::new();
 
-                    let subclass = SubClass {
-                        name: name.to_owned(),
-                        prog_ifs: HashMap::new(),
-                    };
-                    current_subclass = Some((
-                        u8::from_str_radix(id, 16).map_err(|_| Error::invalid_int(id))?,
-                        subclass,
-                    ));
-                }
-                Event::ProgIf { id, name } => {
-                    let (_, subclass) = current_subclass
-                        .as_mut()
-                        .ok_or_else(Error::no_current_subclass)?;
-
-                    subclass.prog_ifs.insert(
-                        u8::from_str_radix(id, 16).map_err(|_| Error::invalid_int(id))?,
-                        name.to_owned(),
-                    );
-                }
-            }
-        }
-        // Finish writing the last vendor and class
-        if let Some((device_id, device)) = current_device.take() {
-            let (_, vendor) = current_vendor
-                .as_mut()
-                .ok_or_else(Error::no_current_vendor)?;
-            vendor.devices.insert(device_id, device);
-        }
-        if let Some((vendor_id, vendor)) = current_vendor.take() {
-            vendors.insert(vendor_id, vendor);
+        while let Some(event) = parser.next_event()? {
+            builder.push(event);
         }
 
-        if let Some((subclass_id, subclass)) = current_subclass.take() {
-            let (_, class) = current_class.as_mut().ok_or_else(Error::no_current_class)?;
+        Ok(builder.finish(parser.metadata().clone()))
+    }
+}
 
-            class.subclasses.insert(subclass_id, subclass);
-        }
-        if let Some((class_id, class)) = current_class.take() {
-            classes.insert(class_id, class);
+impl<'a> Database<&'a str> {
+    /// Parse a database that borrows all names from the given input,
+    /// without allocating a string for every entry.
+    ///
+    /// # Errors
+    /// Returns an error whenever there's a parsing error
+    pub fn parse_str(input: &'a str) -> Result<Self, Error> {
+        let (db, _) = Self::parse_str_with_options(input, ParseOptions::default())?;
+        Ok(db)
+    }
+
+    /// Parse a borrowed database with custom options.
+    /// In lenient mode the lines that were skipped are returned alongside the database.
+    ///
+    /// # Errors
+    /// Returns an error in strict mode whenever there's a parsing error
+    pub fn parse_str_with_options(
+        input: &'a str,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<Warning>), Error> {
+        let mut parser = StrParser::with_options(input, options);
+        let mut builder = Builder::new();
+
+        while let Some(event) = parser.next_event()? {
+            builder.push(event);
         }
 
-        vendors.shrink_to_fit();
-        classes.shrink_to_fit();
+        let db = builder.finish(parser.metadata().clone());
+        Ok((db, parser.take_warnings()))
+    }
 
-        Ok(Self { vendors, classes })
+    /// Parse a borrowed database from raw bytes, which are checked to be valid UTF-8 up front
+    ///
+    /// # Errors
+    /// Returns an error when the input is not valid UTF-8 or whenever there's a parsing error
+    pub fn parse_bytes(input: &'a [u8]) -> Result<Self, Error> {
+        let input = core::str::from_utf8(input).map_err(Error::InvalidUtf8)?;
+        Self::parse_str(input)
     }
+}
 
-    fn open_file() -> Result<File, Error> {
-        if let Some(path) = DB_PATHS
-            .iter()
-            .find(|path| Path::exists(&PathBuf::from(path)))
-        {
-            Ok(File::open(path)?)
-        } else {
-            Err(Error::FileNotFound)
+impl<S: AsRef<str>> Database<S> {
+    /// Compare the versions of two databases, using the date as a tie-breaker.
+    /// Returns `None` when either database has no version in its header.
+    #[must_use]
+    pub fn compare_version<T>(&self, other: &Database<T>) -> Option<Ordering> {
+        let ordering = self.metadata.version?.cmp(&other.metadata.version?);
+
+        match (self.metadata.date, other.metadata.date) {
+            (Some(date), Some(other_date)) => Some(ordering.then(date.cmp(&other_date))),
+            _ => Some(ordering),
         }
     }
 
@@ -248,13 +272,13 @@ impl Database {
         let mut subdevice_name = None;
 
         if let Some(vendor) = self.vendors.get(&vendor_id) {
-            vendor_name = Some(vendor.name.as_str());
+            vendor_name = Some(vendor.name.as_ref());
 
             if let Some(device) = vendor.devices.get(&model_id) {
-                device_name = Some(device.name.as_str());
+                device_name = Some(device.name.as_ref());
 
                 if let Some(subvendor) = self.vendors.get(&subsys_vendor_id) {
-                    subvendor_name = Some(subvendor.name.as_str());
+                    subvendor_name = Some(subvendor.name.as_ref());
                 }
 
                 let subdevice_id = SubDeviceId {
@@ -262,7 +286,7 @@ impl Database {
                     subdevice: subsys_model_id,
                 };
 
-                subdevice_name = device.subdevices.get(&subdevice_id).map(String::as_str);
+                subdevice_name = device.subdevices.get(&subdevice_id).map(AsRef::as_ref);
             }
         }
 
@@ -273,16 +297,93 @@ impl Database {
             subdevice_name,
         }
     }
+
+    /// Look up the names of a class, subclass and programming interface
+    #[must_use]
+    pub fn get_class_info(&self, class_id: u8, subclass_id: u8, prog_if_id: u8) -> ClassInfo<'_> {
+        let mut class_name = None;
+        let mut subclass_name = None;
+        let mut prog_if_name = None;
+
+        if let Some(class) = self.classes.get(&class_id) {
+            class_name = Some(class.name.as_ref());
+
+            if let Some(subclass) = class.subclasses.get(&subclass_id) {
+                subclass_name = Some(subclass.name.as_ref());
+                prog_if_name = subclass.prog_ifs.get(&prog_if_id).map(AsRef::as_ref);
+            }
+        }
+
+        ClassInfo {
+            class_name,
+            subclass_name,
+            prog_if_name,
+        }
+    }
+
+    /// Look up the class names from a packed 24-bit class code,
+    /// as found in the sysfs `class` file (e.g. `0x030000`).
+    /// All names are empty when the code doesn't fit in 24 bits.
+    #[must_use]
+    pub fn get_class_info_by_code(&self, class_code: u32) -> ClassInfo<'_> {
+        let [0, class_id, subclass_id, prog_if_id] = class_code.to_be_bytes() else {
+            return ClassInfo::default();
+        };
+        self.get_class_info(class_id, subclass_id, prog_if_id)
+    }
+
+    /// Get the name of any entry in the database
+    #[must_use]
+    pub fn get_name(&self, entry: EntryId) -> Option<&str> {
+        let name = match entry {
+            EntryId::Vendor(vendor) => &self.vendors.get(&vendor)?.name,
+            EntryId::Device { vendor, device } => {
+                &self.vendors.get(&vendor)?.devices.get(&device)?.name
+            }
+            EntryId::Subdevice {
+                vendor,
+                device,
+                subvendor,
+                subdevice,
+            } => self
+                .vendors
+                .get(&vendor)?
+                .devices
+                .get(&device)?
+                .subdevices
+                .get(&SubDeviceId {
+                    subvendor,
+                    subdevice,
+                })?,
+            EntryId::Class(class) => &self.classes.get(&class)?.name,
+            EntryId::SubClass { class, subclass } => {
+                &self.classes.get(&class)?.subclasses.get(&subclass)?.name
+            }
+            EntryId::ProgIf {
+                class,
+                subclass,
+                prog_if,
+            } => self
+                .classes
+                .get(&class)?
+                .subclasses
+                .get(&subclass)?
+                .prog_ifs
+                .get(&prog_if)?,
+        };
+        Some(name.as_ref())
+    }
 }
 
 /// Try to find the name of a vendor by its id.
-/// This will search the database from one of the known file paths for the name.
+/// This will look up the name in the shared database from [`global`], which is read from one of the known file paths once.
 ///
 /// # Errors
-/// Returns an error when the file can't be read or when parsing fails
+/// Returns an error when the shared database has not been read yet and the file can't be read or when parsing fails
+#[cfg(feature = "std")]
 pub fn find_vendor_name(vendor_id: u16) -> Result<Option<String>, Error> {
-    let reader = Database::open_file()?;
-    find_vendor_name_with_reader(reader, vendor_id)
+    let vendor = try_global()?.vendors.get(&vendor_id);
+    Ok(vendor.map(|vendor| vendor.name.clone()))
 }
 
 /// Try to find the name of a vendor by its id.
@@ -290,12 +391,11 @@ pub fn find_vendor_name(vendor_id: u16) -> Result<Option<String>, Error> {
 ///
 /// # Errors
 /// Returns an error when parsing fails
+#[cfg(feature = "std")]
 pub fn find_vendor_name_with_reader<R: Read>(
     reader: R,
     vendor_id: u16,
 ) -> Result<Option<String>, Error> {
-    let vendor_id = format!("{vendor_id:x?}");
-
     let mut parser = Parser::new(BufReader::new(reader));
 
     while let Some(event) = parser.next_event()? {
@@ -310,13 +410,14 @@ pub fn find_vendor_name_with_reader<R: Read>(
 }
 
 /// Try to find the name of a device by its vendor and device id.
-/// This will search the database from one of the known file paths for the name.
+/// This will look up the name in the shared database from [`global`], which is read from one of the known file paths once.
 ///
 /// # Errors
-/// Returns an error when the file can't be read or when parsing fails
+/// Returns an error when the shared database has not been read yet and the file can't be read or when parsing fails
+#[cfg(feature = "std")]
 pub fn find_device_name(vendor_id: u16, device_id: u16) -> Result<Option<String>, Error> {
-    let reader = Database::open_file()?;
-    find_device_name_with_reader(reader, vendor_id, device_id)
+    let info = try_global()?.get_device_info(vendor_id, device_id, 0, 0);
+    Ok(info.device_name.map(str::to_owned))
 }
 
 /// Try to find the name of a device by its vendor and device id.
@@ -324,14 +425,12 @@ pub fn find_device_name(vendor_id: u16, device_id: u16) -> Result<Option<String>
 ///
 /// # Errors
 /// Returns an error when parsing fails
+#[cfg(feature = "std")]
 pub fn find_device_name_with_reader<R: Read>(
     reader: R,
     vendor_id: u16,
     device_id: u16,
 ) -> Result<Option<String>, Error> {
-    let vendor_id = format!("{vendor_id:x?}");
-    let device_id = format!("{device_id:x?}");
-
     let mut parser = Parser::new(BufReader::new(reader));
 
     while let Some(event) = parser.next_event()? {
@@ -339,10 +438,8 @@ pub fn find_device_name_with_reader<R: Read>(
             if id == vendor_id {
                 while let Some(event) = parser.next_event()? {
                     match event {
-                        Event::Device { id, name } => {
-                            if id == device_id {
-                                return Ok(Some(name.to_owned()));
-                            }
+                        Event::Device { id, name } if id == device_id => {
+                            return Ok(Some(name.to_owned()));
                         }
                         Event::Vendor { .. } => break,
                         _ => (),
@@ -358,24 +455,24 @@ pub fn find_device_name_with_reader<R: Read>(
 }
 
 /// Try to find the name of a subdevice by its ids.
-/// This will search the database from the given reader for the name.
+/// This will look up the name in the shared database from [`global`], which is read from one of the known file paths once.
 ///
 /// # Errors
-/// Returns an error when parsing fails
+/// Returns an error when the shared database has not been read yet and the file can't be read or when parsing fails
+#[cfg(feature = "std")]
 pub fn find_subdevice_name(
     parent_vendor_id: u16,
     parent_device_id: u16,
     subvendor_id: u16,
     subdevice_id: u16,
 ) -> Result<Option<String>, Error> {
-    let reader = Database::open_file()?;
-    find_subdevice_name_with_reader(
-        reader,
+    let info = try_global()?.get_device_info(
         parent_vendor_id,
         parent_device_id,
         subvendor_id,
         subdevice_id,
-    )
+    );
+    Ok(info.subdevice_name.map(str::to_owned))
 }
 
 /// Try to find the name of a subdevice by its ids.
@@ -383,6 +480,7 @@ pub fn find_subdevice_name(
 ///
 /// # Errors
 /// Returns an error when parsing fails
+#[cfg(feature = "std")]
 pub fn find_subdevice_name_with_reader<R: Read>(
     reader: R,
     parent_vendor_id: u16,
@@ -390,11 +488,6 @@ pub fn find_subdevice_name_with_reader<R: Read>(
     subvendor_id: u16,
     subdevice_id: u16,
 ) -> Result<Option<String>, Error> {
-    let parent_vendor_id = format!("{parent_vendor_id:x?}");
-    let parent_device_id = format!("{parent_device_id:x?}");
-    let subvendor_id = format!("{subvendor_id:x?}");
-    let subdevice_id = format!("{subdevice_id:x?}");
-
     let mut parser = Parser::new(BufReader::new(reader));
 
     while let Some(event) = parser.next_event()? {
@@ -402,27 +495,23 @@ pub fn find_subdevice_name_with_reader<R: Read>(
             if id == parent_vendor_id {
                 while let Some(event) = parser.next_event()? {
                     match event {
-                        Event::Device { id, .. } => {
-                            if id == parent_device_id {
-                                while let Some(event) = parser.next_event()? {
-                                    match event {
-                                        Event::Subdevice {
-                                            subvendor,
-                                            subdevice,
-                                            subsystem_name,
-                                        } => {
-                                            if subvendor == subvendor_id
-                                                && subdevice == subdevice_id
-                                            {
-                                                return Ok(Some(subsystem_name.to_owned()));
-                                            }
+                        Event::Device { id, .. } if id == parent_device_id => {
+                            while let Some(event) = parser.next_event()? {
+                                match event {
+                                    Event::Subdevice {
+                                        subvendor,
+                                        subdevice,
+                                        subsystem_name,
+                                    } => {
+                                        if subvendor == subvendor_id && subdevice == subdevice_id {
+                                            return Ok(Some(subsystem_name.to_owned()));
                                         }
-                                        _ => break,
                                     }
+                                    _ => break,
                                 }
-
-                                break;
                             }
+
+                            break;
                         }
                         Event::Vendor { .. } => break,
                         _ => (),
@@ -436,3 +525,17 @@ pub fn find_subdevice_name_with_reader<R: Read>(
 
     Ok(None)
 }
+
+/// Write to a unique temporary file next to the destination first, so that concurrent readers
+/// never see a partial file and concurrent writers don't overwrite each other's temporary file
+#[cfg(feature = "std")]
+pub(crate) fn write_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(data)?;
+    file.persist(path)?;
+    Ok(())
+}
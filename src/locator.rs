@@ -0,0 +1,239 @@
+//! Finding the database file on the system.
+//!
+//! The [`DatabaseLocator`] checks the following locations in order and uses the first file that exists:
+//!
+//! 1. The file in the `PCIIDS_PATH` environment variable. When it is set, no other location is checked.
+//! 2. Paths that were added with [`DatabaseLocator::path`].
+//! 3. `pci.ids` in the `HWDATA_DIR` environment variable.
+//! 4. `hwdata/pci.ids` and `misc/pci.ids` in every directory of `XDG_DATA_DIRS`,
+//!    which defaults to `/usr/local/share:/usr/share`.
+//! 5. Known distribution paths, such as the one on NixOS. The list is kept in `src/lib.rs`,
+//!    where packagers substitute `@hwdata@` with the path of their hwdata package.
+//!    The entry is skipped when it hasn't been substituted.
+//!
+//! In the directories from 3 to 5, compressed files (such as `pci.ids.gz`) are checked as well
+//! when the matching feature is enabled.
+use crate::{compression::Compression, Database, Error, DB_PATHS};
+use std::{
+    env,
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const FILE_NAME: &str = "pci.ids";
+const XDG_DATA_DIRS_DEFAULT: &str = "/usr/local/share:/usr/share";
+/// Subdirectories of the data directories that distributions install the file to
+const DATA_SUBDIRS: &[&str] = &["hwdata", "misc"];
+/// Placeholder that Nix packaging substitutes with the hwdata store path.
+/// It is split so that the substitution doesn't replace it as well.
+const HWDATA_PLACEHOLDER: &str = concat!("@", "hwdata@");
+
+type EnvLookup = Arc<dyn Fn(&str) -> Option<OsString> + Send + Sync>;
+
+/// Configurable search for the database file.
+///
+/// ```rust,no_run
+/// use pciid_parser::locator::DatabaseLocator;
+///
+/// let (db, path) = DatabaseLocator::new()
+///     .path("/opt/hwdata/pci.ids")
+///     .read()
+///     .unwrap();
+/// println!("Read {} vendors from {}", db.vendors.len(), path.display());
+/// ```
+#[derive(Clone)]
+pub struct DatabaseLocator {
+    paths: Vec<PathBuf>,
+    env: Option<EnvLookup>,
+    default_paths: bool,
+}
+
+impl fmt::Debug for DatabaseLocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DatabaseLocator")
+            .field("paths", &self.paths)
+            .field("use_env", &self.env.is_some())
+            .field("default_paths", &self.default_paths)
+            .finish()
+    }
+}
+
+impl Default for DatabaseLocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DatabaseLocator {
+    /// Search the environment variables and the default paths
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            paths: Vec::new(),
+            env: Some(Arc::new(|name: &str| env::var_os(name))),
+            default_paths: true,
+        }
+    }
+
+    /// Check the given file before the default paths
+    #[must_use]
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Ignore the `PCIIDS_PATH`, `HWDATA_DIR` and `XDG_DATA_DIRS` environment variables
+    #[must_use]
+    pub fn without_env(mut self) -> Self {
+        self.env = None;
+        self
+    }
+
+    /// Look the environment variables up with the given function instead of reading the process environment
+    ///
+    /// ```rust
+    /// use pciid_parser::locator::DatabaseLocator;
+    /// use std::path::PathBuf;
+    ///
+    /// let locator = DatabaseLocator::new()
+    ///     .env_lookup(|name| (name == "PCIIDS_PATH").then(|| "/opt/pci.ids".into()));
+    /// assert_eq!(locator.candidates(), [PathBuf::from("/opt/pci.ids")]);
+    /// ```
+    #[must_use]
+    pub fn env_lookup(
+        mut self,
+        lookup: impl Fn(&str) -> Option<OsString> + Send + Sync + 'static,
+    ) -> Self {
+        self.env = Some(Arc::new(lookup));
+        self
+    }
+
+    /// Only check the paths that were added with [`DatabaseLocator::path`] and the environment variables
+    #[must_use]
+    pub fn without_default_paths(mut self) -> Self {
+        self.default_paths = false;
+        self
+    }
+
+    /// Every path that is checked, in order and without duplicates
+    #[must_use]
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        if let Some(path) = self.env_var("PCIIDS_PATH") {
+            return vec![PathBuf::from(path)];
+        }
+
+        let mut dirs = Vec::new();
+        if let Some(hwdata_dir) = self.env_var("HWDATA_DIR") {
+            dirs.push(PathBuf::from(hwdata_dir));
+        }
+        if self.default_paths {
+            let data_dirs = self
+                .env_var("XDG_DATA_DIRS")
+                .unwrap_or_else(|| XDG_DATA_DIRS_DEFAULT.into());
+            for data_dir in env::split_paths(&data_dirs) {
+                // Relative paths are invalid in XDG_DATA_DIRS and should be ignored
+                if data_dir.is_absolute() {
+                    dirs.extend(DATA_SUBDIRS.iter().map(|subdir| data_dir.join(subdir)));
+                }
+            }
+        }
+
+        let mut files: Vec<PathBuf> = dirs.iter().map(|dir| dir.join(FILE_NAME)).collect();
+        if self.default_paths {
+            files.extend(known_paths(DB_PATHS));
+        }
+
+        let mut candidates = self.paths.clone();
+        for file in files {
+            candidates.extend(with_compressed_variants(file));
+        }
+
+        let mut unique = Vec::with_capacity(candidates.len());
+        for path in candidates {
+            if !unique.contains(&path) {
+                unique.push(path);
+            }
+        }
+        unique
+    }
+
+    /// Find the first database file that exists
+    ///
+    /// # Errors
+    /// Returns [`Error::FileNotFound`] with every path that was checked when none of them exist
+    pub fn locate(&self) -> Result<PathBuf, Error> {
+        let candidates = self.candidates();
+        match candidates.iter().find(|path| path.is_file()) {
+            Some(path) => Ok(path.clone()),
+            None => Err(Error::FileNotFound(candidates)),
+        }
+    }
+
+    /// Read the database from the first file that exists, returning it alongside the path it was read from
+    ///
+    /// # Errors
+    /// Returns an error when no file could be found, or when reading or parsing it fails
+    pub fn read(&self) -> Result<(Database, PathBuf), Error> {
+        let path = self.locate()?;
+        let db = Database::read_from_file(&path)?;
+        Ok((db, path))
+    }
+
+    fn env_var(&self, name: &str) -> Option<OsString> {
+        let lookup = self.env.as_ref()?;
+        lookup(name).filter(|value| !value.is_empty())
+    }
+}
+
+/// The known paths, without the ones where the hwdata placeholder wasn't substituted
+fn known_paths<'a>(paths: &'a [&str]) -> impl Iterator<Item = PathBuf> + 'a {
+    paths
+        .iter()
+        .filter(|path| !path.contains(HWDATA_PLACEHOLDER))
+        .map(PathBuf::from)
+}
+
+/// The path itself, followed by the compressed files next to it that this build can read
+fn with_compressed_variants(path: PathBuf) -> impl Iterator<Item = PathBuf> {
+    let compressed = Compression::ENABLED.iter().map({
+        let path = path.clone();
+        move |compression| append_extension(&path, compression.extension())
+    });
+    std::iter::once(path).chain(compressed)
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::known_paths;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn hwdata_placeholder() {
+        let unsubstituted = ["/usr/share/hwdata/pci.ids", "@hwdata@/share/hwdata/pci.ids"];
+        assert_eq!(
+            known_paths(&unsubstituted).collect::<Vec<_>>(),
+            [PathBuf::from("/usr/share/hwdata/pci.ids")]
+        );
+
+        let substituted = [
+            "/usr/share/hwdata/pci.ids",
+            "/nix/store/abc-hwdata-0.380/share/hwdata/pci.ids",
+        ];
+        assert_eq!(
+            known_paths(&substituted).collect::<Vec<_>>(),
+            [
+                PathBuf::from("/usr/share/hwdata/pci.ids"),
+                PathBuf::from("/nix/store/abc-hwdata-0.380/share/hwdata/pci.ids")
+            ]
+        );
+    }
+}
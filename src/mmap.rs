@@ -0,0 +1,64 @@
+//! Memory-mapped database files
+use crate::{compression::Compression, error::Error, BorrowedDatabase, Database};
+use memmap2::Mmap;
+use std::{
+    fs::File,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+/// A database parsed in place from a memory-mapped file.
+///
+/// All names point into the mapping, which is kept alive for as long as the handle exists.
+pub struct MmapDatabase {
+    // Declared before the mapping so that it's dropped first
+    db: BorrowedDatabase<'static>,
+    _mmap: Mmap,
+}
+
+impl MmapDatabase {
+    /// The parsed database, borrowing from the mapping
+    #[must_use]
+    pub fn database(&self) -> &BorrowedDatabase<'_> {
+        &self.db
+    }
+}
+
+impl std::fmt::Debug for MmapDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MmapDatabase")
+            .field("db", &self.db)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Database {
+    /// Map the given file into memory and parse it without copying the names.
+    /// Compressed files are not supported.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated while the returned handle is alive.
+    /// Package managers usually replace `pci.ids` by renaming a new file over it, which is fine.
+    ///
+    /// # Errors
+    /// Returns an error when the file can't be mapped, is compressed, is not valid UTF-8 or when parsing fails
+    pub unsafe fn open_mmap<P: AsRef<Path>>(path: P) -> Result<MmapDatabase, Error> {
+        let file = File::open(path)?;
+        // SAFETY: the caller guarantees that the file is not modified while mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if let Some(compression) = Compression::detect(&mmap) {
+            let message = format!("{compression} compressed databases can't be memory-mapped");
+            return Err(io::Error::new(ErrorKind::InvalidData, message).into());
+        }
+        let text = std::str::from_utf8(&mmap)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+
+        // SAFETY: the mapped memory doesn't move when the `Mmap` is moved, and the handle
+        // only hands out borrows that are bound to its own lifetime.
+        let text: &'static str = unsafe { &*std::ptr::from_ref::<str>(text) };
+        let db = BorrowedDatabase::parse_str(text)?;
+
+        Ok(MmapDatabase { db, _mmap: mmap })
+    }
+}
@@ -0,0 +1,114 @@
+//! Kernel `modalias` strings for PCI devices.
+//!
+//! ```rust
+//! use pciid_parser::modalias::Modalias;
+//!
+//! let modalias: Modalias = "pci:v00001002d000067DFsv00001DA2sd0000E387bc03sc00i00"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(modalias.vendor_id, 0x1002);
+//! assert_eq!(modalias.class_code(), 0x030000);
+//! ```
+use crate::{
+    error::Error,
+    schema::{ClassInfo, DeviceInfo},
+    Database,
+};
+use alloc::borrow::ToOwned;
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Ids extracted from a modalias such as `pci:v00001002d000067DFsv00001DA2sd0000E387bc03sc00i00`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modalias {
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub subsystem_vendor_id: u16,
+    pub subsystem_device_id: u16,
+    pub class: u8,
+    pub subclass: u8,
+    pub prog_if: u8,
+}
+
+/// Names resolved from a [`Modalias`]
+#[derive(Default, Clone, Debug)]
+pub struct ModaliasInfo<'a> {
+    pub device_info: DeviceInfo<'a>,
+    pub class_info: ClassInfo<'a>,
+}
+
+impl Modalias {
+    /// Packed 24-bit class code
+    #[must_use]
+    pub fn class_code(&self) -> u32 {
+        u32::from_be_bytes([0, self.class, self.subclass, self.prog_if])
+    }
+}
+
+impl FromStr for Modalias {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidModalias(s.to_owned());
+
+        let mut rest = s.strip_prefix("pci:").ok_or_else(invalid)?;
+        let mut field = |tag: &str, len: usize| -> Result<u32, Error> {
+            let value = rest.strip_prefix(tag).ok_or_else(invalid)?;
+            let (digits, remaining) = value.split_at_checked(len).ok_or_else(invalid)?;
+            rest = remaining;
+            u32::from_str_radix(digits, 16).map_err(|_| invalid())
+        };
+        let id = |value: u32| u16::try_from(value).map_err(|_| invalid());
+        let class = |value: u32| u8::try_from(value).map_err(|_| invalid());
+
+        let modalias = Self {
+            vendor_id: id(field("v", 8)?)?,
+            device_id: id(field("d", 8)?)?,
+            subsystem_vendor_id: id(field("sv", 8)?)?,
+            subsystem_device_id: id(field("sd", 8)?)?,
+            class: class(field("bc", 2)?)?,
+            subclass: class(field("sc", 2)?)?,
+            prog_if: class(field("i", 2)?)?,
+        };
+
+        if rest.is_empty() {
+            Ok(modalias)
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl Display for Modalias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pci:v{:08X}d{:08X}sv{:08X}sd{:08X}bc{:02X}sc{:02X}i{:02X}",
+            self.vendor_id,
+            self.device_id,
+            self.subsystem_vendor_id,
+            self.subsystem_device_id,
+            self.class,
+            self.subclass,
+            self.prog_if
+        )
+    }
+}
+
+impl<S: AsRef<str>> Database<S> {
+    /// Look up the device and class names for the ids in a modalias
+    #[must_use]
+    pub fn lookup_modalias(&self, modalias: &Modalias) -> ModaliasInfo<'_> {
+        ModaliasInfo {
+            device_info: self.get_device_info(
+                modalias.vendor_id,
+                modalias.device_id,
+                modalias.subsystem_vendor_id,
+                modalias.subsystem_device_id,
+            ),
+            class_info: self.get_class_info(modalias.class, modalias.subclass, modalias.prog_if),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Names split into their parts.
+//!
+//! Many names in the database consist of a codename followed by a bracketed list of marketing names,
+//! such as `Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]`, or of a company name followed by
+//! its short name, such as `Advanced Micro Devices, Inc. [AMD/ATI]`.
+//!
+//! ```rust
+//! use pciid_parser::name::ParsedName;
+//!
+//! let name = ParsedName::parse("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]");
+//! assert_eq!(name.base, "Ellesmere");
+//! assert_eq!(name.short, Some("Radeon RX 470/480/570/570X/580/580X/590"));
+//! assert_eq!(name.variants[4], "Radeon RX 580");
+//! assert_eq!(name.variant_in("Radeon RX 580 Pulse 4GB"), Some("Radeon RX 580"));
+//! ```
+use crate::{
+    schema::{Device, Vendor},
+    search::tokenize,
+};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// A name split into the part before the brackets and the names inside them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedName<'a> {
+    /// The name without the bracketed part, such as `Ellesmere`. Empty if the name starts with a bracket.
+    pub base: &'a str,
+    /// The text inside the brackets, such as `AMD/ATI`
+    pub short: Option<&'a str>,
+    /// The bracketed names, expanded to full names such as `Radeon RX 480`
+    pub variants: Vec<Cow<'a, str>>,
+}
+
+impl<'a> ParsedName<'a> {
+    /// Split a name into its parts.
+    ///
+    /// Variants that start with a digit share the words before the model number of the first variant
+    /// in their group. Groups are separated by ` / `, so `[Radeon RX 6800/6800 XT / 6900 XT]` becomes
+    /// `Radeon RX 6800`, `Radeon RX 6800 XT` and `Radeon RX 6900 XT`.
+    #[must_use]
+    pub fn parse(name: &'a str) -> Self {
+        let Some((base, rest)) = name.split_once('[') else {
+            return Self {
+                base: name.trim(),
+                short: None,
+                variants: Vec::new(),
+            };
+        };
+        let short = rest
+            .rsplit_once(']')
+            .map_or(rest, |(short, _)| short)
+            .trim();
+
+        let mut variants = Vec::new();
+        let mut prefix = String::new();
+
+        for group in short.split(" / ") {
+            for (index, item) in group.split('/').map(str::trim).enumerate() {
+                if item.is_empty() {
+                    continue;
+                }
+
+                let variant =
+                    if !prefix.is_empty() && item.starts_with(|c: char| c.is_ascii_digit()) {
+                        Cow::Owned(prefix.clone() + item)
+                    } else {
+                        Cow::Borrowed(item)
+                    };
+
+                if index == 0 {
+                    prefix = model_prefix(&variant).to_string();
+                }
+                variants.push(variant);
+            }
+        }
+
+        Self {
+            base: base.trim(),
+            short: Some(short),
+            variants,
+        }
+    }
+
+    /// Find the variant that is mentioned in another name, such as the name of a subsystem.
+    /// Words are compared ignoring case, and the longest mentioned variant wins.
+    #[must_use]
+    pub fn variant_in(&self, text: &str) -> Option<&str> {
+        let words = tokenize(text);
+
+        self.variants
+            .iter()
+            .filter(|variant| {
+                let variant_words = tokenize(variant);
+                !variant_words.is_empty()
+                    && words
+                        .windows(variant_words.len())
+                        .any(|window| window == variant_words.as_slice())
+            })
+            .max_by_key(|variant| variant.len())
+            .map(AsRef::as_ref)
+    }
+}
+
+impl<S: AsRef<str>> Vendor<S> {
+    /// The name split into its parts, such as `Advanced Micro Devices, Inc.` and `AMD/ATI`
+    #[must_use]
+    pub fn parsed_name(&self) -> ParsedName<'_> {
+        ParsedName::parse(self.name.as_ref())
+    }
+}
+
+impl<S: AsRef<str>> Device<S> {
+    /// The name split into its parts, such as `Ellesmere` and its marketing names
+    #[must_use]
+    pub fn parsed_name(&self) -> ParsedName<'_> {
+        ParsedName::parse(self.name.as_ref())
+    }
+}
+
+/// The words before the first one that starts with a digit, including the trailing space
+fn model_prefix(name: &str) -> &str {
+    name.match_indices(' ')
+        .find(|(index, _)| name[index + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        .map_or("", |(index, _)| &name[..=index])
+}
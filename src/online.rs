@@ -0,0 +1,374 @@
+//! Fetching the database from an online source, with an optional on-disk cache
+use crate::{
+    compression::{self, Compression},
+    Database, Error,
+};
+use std::{
+    fs::{self, File},
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+const BASE_URL: &str = "https://pci-ids.ucw.cz/v2.2";
+const FILE_NAME: &str = "pci.ids";
+const VALIDATORS_FILE_NAME: &str = "pci.ids.validators";
+const USER_AGENT: &str = concat!("pciid-parser/", env!("CARGO_PKG_VERSION"));
+const SIZE_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// Where to fetch the database from and how to cache it.
+///
+/// The base URLs are tried in order until one of them succeeds.
+/// With a cache directory, the last downloaded copy is kept on disk and every request is conditional on it
+/// (`If-None-Match`/`If-Modified-Since`), so an unchanged file is not downloaded again.
+/// The cached copy is only used when it was downloaded from one of the URLs of the source,
+/// and the validators are only sent to the URL they came from.
+///
+/// ```rust,no_run
+/// use pciid_parser::{compression::Compression, online::OnlineSource, Database};
+/// use std::time::Duration;
+///
+/// let source = OnlineSource::new()
+///     .base_url("https://mirror.example.com/pciids")
+///     .fallback_base_url("https://pci-ids.ucw.cz/v2.2")
+///     .compression(Compression::Gzip)
+///     .connect_timeout(Duration::from_secs(5))
+///     .cache_dir("/var/cache/pciids")
+///     .max_age(Duration::from_secs(24 * 60 * 60));
+/// let db = Database::get_online_with(&source).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct OnlineSource {
+    base_url: String,
+    fallback_base_urls: Vec<String>,
+    compression: Option<Compression>,
+    cache_dir: Option<PathBuf>,
+    max_age: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    user_agent: String,
+    size_limit: u64,
+}
+
+impl Default for OnlineSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OnlineSource {
+    /// The official `pci-ids.ucw.cz` source, without a cache or timeouts
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            base_url: BASE_URL.to_owned(),
+            fallback_base_urls: Vec::new(),
+            compression: None,
+            cache_dir: None,
+            max_age: None,
+            connect_timeout: None,
+            read_timeout: None,
+            user_agent: USER_AGENT.to_owned(),
+            size_limit: SIZE_LIMIT,
+        }
+    }
+
+    /// Set the URL of the directory that `pci.ids` is fetched from, replacing any previous ones
+    #[must_use]
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self.fallback_base_urls.clear();
+        self
+    }
+
+    /// Add a URL to try when fetching from the previous ones fails
+    #[must_use]
+    pub fn fallback_base_url(mut self, url: impl Into<String>) -> Self {
+        self.fallback_base_urls.push(url.into());
+        self
+    }
+
+    /// Download a compressed file (such as `pci.ids.gz`) instead, which requires the matching feature
+    #[must_use]
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Keep the downloaded file in the given directory, which is created when needed
+    #[must_use]
+    pub fn cache_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(path.into());
+        self
+    }
+
+    /// Use the cached file without making a request as long as it was fetched or revalidated
+    /// within the given age. Has no effect without a cache directory.
+    #[must_use]
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Limit the time it takes to connect to each URL
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Limit the time it takes to receive the response headers, and separately the whole body, from each URL.
+    ///
+    /// This is a deadline for the entire download rather than an inactivity timeout,
+    /// so it has to leave room for downloading the file over the slowest expected link.
+    #[must_use]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the `User-Agent` header, which defaults to `pciid-parser/<version>`
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Set the maximum size of the downloaded file in bytes. Defaults to 10 MiB.
+    ///
+    /// The limit applies both to the downloaded bytes and to the decompressed file,
+    /// which fails with an [`Error::Io`] when it's exceeded.
+    #[must_use]
+    pub fn size_limit(mut self, limit: u64) -> Self {
+        self.size_limit = limit;
+        self
+    }
+
+    /// The full URLs of the file, in the order they're tried
+    #[must_use]
+    pub fn urls(&self) -> Vec<String> {
+        std::iter::once(&self.base_url)
+            .chain(&self.fallback_base_urls)
+            .map(|base_url| self.file_url(base_url))
+            .collect()
+    }
+
+    fn file_url(&self, base_url: &str) -> String {
+        let extension = self.compression.map_or("", Compression::extension);
+        format!("{}/{FILE_NAME}{extension}", base_url.trim_end_matches('/'))
+    }
+
+    /// Fetch the database, or read it from the cache when it's fresh or the server reports it as unchanged.
+    ///
+    /// Failing to update the cache is not an error, since the database itself was fetched.
+    ///
+    /// # Errors
+    /// Returns the error of the last URL when fetching from all of them fails,
+    /// or an error when the requested compression is not enabled
+    pub fn fetch(&self) -> Result<Database, Error> {
+        if let Some(compression) = self.compression {
+            if !Compression::ENABLED.contains(&compression) {
+                return Err(Error::UnsupportedCompression(compression));
+            }
+        }
+
+        let cache = self.cache_dir.as_deref().map(Cache::new);
+        let cached = cache.as_ref().and_then(Cache::validators);
+        if let (Some(cache), Some(cached), Some(max_age)) = (&cache, &cached, self.max_age) {
+            let from_source = self.urls().iter().any(|url| cached.is_from(url));
+            if from_source && cache.age().is_some_and(|age| age < max_age) {
+                return cache.read();
+            }
+        }
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_connect(self.connect_timeout)
+            .timeout_recv_response(self.read_timeout)
+            .timeout_recv_body(self.read_timeout)
+            .user_agent(&self.user_agent)
+            .build()
+            .into();
+
+        let fetch = |base_url| {
+            let url = self.file_url(base_url);
+            let cached = cached.as_ref().filter(|cached| cached.is_from(&url));
+            self.fetch_url(&agent, &url, cache.as_ref(), cached)
+        };
+
+        let mut result = fetch(&self.base_url);
+        for base_url in &self.fallback_base_urls {
+            if result.is_ok() {
+                break;
+            }
+            result = fetch(base_url);
+        }
+        result
+    }
+
+    /// Fetch the database like [`OnlineSource::fetch`], on a blocking thread of the tokio runtime
+    /// since the HTTP client is synchronous
+    ///
+    /// # Errors
+    /// Returns the same errors as [`OnlineSource::fetch`]
+    ///
+    /// # Panics
+    /// Panics when called outside of a tokio runtime
+    #[cfg(feature = "async")]
+    pub async fn fetch_async(&self) -> Result<Database, Error> {
+        let source = self.clone();
+        crate::asynchronous::spawn_blocking(move || source.fetch()).await
+    }
+
+    fn fetch_url(
+        &self,
+        agent: &ureq::Agent,
+        url: &str,
+        cache: Option<&Cache>,
+        cached: Option<&Validators>,
+    ) -> Result<Database, Error> {
+        let mut request = agent.get(url);
+        if let Some(validators) = cached {
+            if let Some(etag) = &validators.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let response = request.call()?;
+        if response.status() == 304 {
+            return match (cache, cached) {
+                (Some(cache), Some(_)) => {
+                    cache.touch();
+                    cache.read()
+                }
+                // The request wasn't conditional, so there is nothing to reuse
+                _ => Err(ureq::Error::StatusCode(304).into()),
+            };
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        let validators = Validators {
+            url: Some(url.to_owned()),
+            etag: header("ETag"),
+            last_modified: header("Last-Modified"),
+        };
+
+        let data = response
+            .into_body()
+            .with_config()
+            .limit(self.size_limit)
+            .read_to_vec()?;
+        // Only cache a file that could be parsed and has entries,
+        // so that an error page or an empty response is not served from the cache later
+        let reader = compression::decompress_limited(Cursor::new(&data), self.size_limit)?;
+        let db = Database::parse_db(reader)?;
+        if db.vendors.is_empty() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the downloaded database has no vendors",
+            )));
+        }
+        if let Some(cache) = cache {
+            cache.store(&data, &validators);
+        }
+        Ok(db)
+    }
+}
+
+/// The URL of the cached file and the response headers that identify its version
+#[derive(Debug, Default)]
+struct Validators {
+    url: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn is_from(&self, url: &str) -> bool {
+        self.url.as_deref() == Some(url)
+    }
+}
+
+struct Cache<'a> {
+    dir: &'a Path,
+    data_path: PathBuf,
+    validators_path: PathBuf,
+}
+
+impl<'a> Cache<'a> {
+    fn new(dir: &'a Path) -> Self {
+        Self {
+            dir,
+            data_path: dir.join(FILE_NAME),
+            validators_path: dir.join(VALIDATORS_FILE_NAME),
+        }
+    }
+
+    /// Time since the cached file was last fetched or revalidated
+    fn age(&self) -> Option<Duration> {
+        let modified = fs::metadata(&self.data_path).ok()?.modified().ok()?;
+        Some(modified.elapsed().unwrap_or_default())
+    }
+
+    /// The file is stored as downloaded, so compressed downloads are decompressed again here
+    fn read(&self) -> Result<Database, Error> {
+        Database::read_from_file(&self.data_path)
+    }
+
+    /// The validators of the cached file, or `None` when there is no cached file
+    fn validators(&self) -> Option<Validators> {
+        if !self.data_path.is_file() {
+            return None;
+        }
+
+        let mut validators = Validators::default();
+        let contents = fs::read_to_string(&self.validators_path).unwrap_or_default();
+        for line in contents.lines() {
+            match line.split_once(": ") {
+                Some(("URL", value)) => validators.url = Some(value.to_owned()),
+                Some(("ETag", value)) => validators.etag = Some(value.to_owned()),
+                Some(("Last-Modified", value)) => validators.last_modified = Some(value.to_owned()),
+                _ => (),
+            }
+        }
+        Some(validators)
+    }
+
+    /// Mark the cached file as fresh after the server confirmed it's unchanged
+    fn touch(&self) {
+        if let Ok(file) = File::options().append(true).open(&self.data_path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+    }
+
+    fn store(&self, data: &[u8], validators: &Validators) {
+        if fs::create_dir_all(self.dir).is_err() {
+            return;
+        }
+
+        // The old validators are removed first, so that they never describe the new file
+        let _ = fs::remove_file(&self.validators_path);
+        if crate::write_atomically(&self.data_path, data).is_err() {
+            return;
+        }
+
+        let fields = [
+            ("URL", &validators.url),
+            ("ETag", &validators.etag),
+            ("Last-Modified", &validators.last_modified),
+        ];
+        let contents: String = fields
+            .iter()
+            .filter_map(|(name, value)| Some(format!("{name}: {}\n", value.as_ref()?)))
+            .collect();
+        let _ = crate::write_atomically(&self.validators_path, contents.as_bytes());
+    }
+}
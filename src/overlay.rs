@@ -0,0 +1,204 @@
+//! Local fragments in the `pci.ids` format that patch a base database
+use crate::{error::Error, schema::EntryId, Database};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+
+/// Where the name of an entry comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source<'a> {
+    /// The database the overlays were applied to
+    Base,
+    /// An overlay, numbered in the order in which overlays were applied
+    Overlay {
+        index: usize,
+        /// Only set for overlays that were read from a file
+        path: Option<&'a Path>,
+    },
+}
+
+/// Entries that were defined by overlays
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct OverlaySources {
+    paths: Vec<Option<PathBuf>>,
+    entries: HashMap<EntryId, usize>,
+}
+
+impl Database {
+    /// Read the database from one of the known file paths and apply the `*.ids` files
+    /// from the given directories (such as `/etc/pciids.d`) on top of it.
+    ///
+    /// Directories are applied in the given order and files within a directory are sorted by name,
+    /// so later files take precedence. Directories that don't exist are skipped.
+    ///
+    /// # Errors
+    /// Returns an error when the base database or an overlay can't be read or parsed
+    pub fn read_with_overlays<I, P>(dirs: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut db = Self::read()?;
+
+        for dir in dirs {
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            let mut paths = Vec::new();
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().is_some_and(|extension| extension == "ids") {
+                    paths.push(path);
+                }
+            }
+            paths.sort_unstable();
+
+            for path in paths {
+                db.apply_overlay_file(path)?;
+            }
+        }
+
+        Ok(db)
+    }
+
+    /// Apply an overlay file on top of the database. See [`Database::apply_overlay`].
+    ///
+    /// # Errors
+    /// Returns an error when the file can't be read or parsed
+    pub fn apply_overlay_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        self.apply_overlay_from(file, Some(path.to_owned()))
+    }
+
+    /// Apply an overlay in the `pci.ids` format on top of the database.
+    ///
+    /// Every entry in the overlay is added to the database, replacing the name of an existing entry
+    /// with the same ids. Parent entries need to be repeated in the overlay to nest entries under them,
+    /// so an existing entry that has nested entries in the overlay keeps its name from the database.
+    /// Only the entries whose names were added or changed are reported as coming from the overlay
+    /// by [`Database::source_of`].
+    /// The database is left unchanged when the overlay can't be parsed.
+    ///
+    /// # Errors
+    /// Returns an error when the overlay can't be read or parsed
+    pub fn apply_overlay<R: Read>(&mut self, reader: R) -> Result<(), Error> {
+        self.apply_overlay_from(reader, None)
+    }
+
+    fn apply_overlay_from<R: Read>(
+        &mut self,
+        reader: R,
+        path: Option<PathBuf>,
+    ) -> Result<(), Error> {
+        let overlay = Self::parse_db(reader)?;
+        let index = self.overlays.paths.len();
+        self.overlays.paths.push(path);
+
+        let entries = &mut self.overlays.entries;
+        let mut record = |entry| {
+            entries.insert(entry, index);
+        };
+
+        for (vendor_id, overlay_vendor) in overlay.vendors {
+            let is_new = !self.vendors.contains_key(&vendor_id);
+            let vendor = self.vendors.entry(vendor_id).or_default();
+            let is_parent = !overlay_vendor.devices.is_empty();
+            if merge_name(&mut vendor.name, overlay_vendor.name, is_new, is_parent) {
+                record(EntryId::Vendor(vendor_id));
+            }
+
+            for (device_id, overlay_device) in overlay_vendor.devices {
+                let is_new = !vendor.devices.contains_key(&device_id);
+                let device = vendor.devices.entry(device_id).or_default();
+                let is_parent = !overlay_device.subdevices.is_empty();
+                if merge_name(&mut device.name, overlay_device.name, is_new, is_parent) {
+                    record(EntryId::Device {
+                        vendor: vendor_id,
+                        device: device_id,
+                    });
+                }
+
+                for (subdevice_id, name) in overlay_device.subdevices {
+                    if device.subdevices.get(&subdevice_id) != Some(&name) {
+                        record(EntryId::Subdevice {
+                            vendor: vendor_id,
+                            device: device_id,
+                            subvendor: subdevice_id.subvendor,
+                            subdevice: subdevice_id.subdevice,
+                        });
+                        device.subdevices.insert(subdevice_id, name);
+                    }
+                }
+            }
+        }
+
+        for (class_id, overlay_class) in overlay.classes {
+            let is_new = !self.classes.contains_key(&class_id);
+            let class = self.classes.entry(class_id).or_default();
+            let is_parent = !overlay_class.subclasses.is_empty();
+            if merge_name(&mut class.name, overlay_class.name, is_new, is_parent) {
+                record(EntryId::Class(class_id));
+            }
+
+            for (subclass_id, overlay_subclass) in overlay_class.subclasses {
+                let is_new = !class.subclasses.contains_key(&subclass_id);
+                let subclass = class.subclasses.entry(subclass_id).or_default();
+                let is_parent = !overlay_subclass.prog_ifs.is_empty();
+                if merge_name(&mut subclass.name, overlay_subclass.name, is_new, is_parent) {
+                    record(EntryId::SubClass {
+                        class: class_id,
+                        subclass: subclass_id,
+                    });
+                }
+
+                for (prog_if_id, name) in overlay_subclass.prog_ifs {
+                    if subclass.prog_ifs.get(&prog_if_id) != Some(&name) {
+                        record(EntryId::ProgIf {
+                            class: class_id,
+                            subclass: subclass_id,
+                            prog_if: prog_if_id,
+                        });
+                        subclass.prog_ifs.insert(prog_if_id, name);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Replace the name of an entry with the one from the overlay, returning whether it was replaced.
+/// Existing entries that are only repeated as the parent of other entries keep their name.
+fn merge_name(name: &mut String, overlay_name: String, is_new: bool, is_parent: bool) -> bool {
+    if !is_new && (is_parent || *name == overlay_name) {
+        return false;
+    }
+    *name = overlay_name;
+    true
+}
+
+impl<S: AsRef<str>> Database<S> {
+    /// Find out whether the name of an entry comes from the base database or from an overlay.
+    /// Returns `None` when the entry doesn't exist.
+    #[must_use]
+    pub fn source_of(&self, entry: EntryId) -> Option<Source<'_>> {
+        self.get_name(entry)?;
+
+        let source = match self.overlays.entries.get(&entry) {
+            Some(&index) => Source::Overlay {
+                index,
+                path: self.overlays.paths[index].as_deref(),
+            },
+            None => Source::Base,
+        };
+        Some(source)
+    }
+}
@@ -1,113 +1,465 @@
-use crate::error::Error;
-use std::io::BufRead;
+//! Streaming pull-parser for the `pci.ids` format.
+//!
+//! The [`Parser`] reads the database line by line and yields an [`Event`] for every entry,
+//! without building any maps. This is useful for single-pass scans that only need a few entries:
+//!
+//! ```rust
+//! # #[cfg(feature = "std")] {
+//! use pciid_parser::parser::{Event, Parser};
+//! use std::io::Cursor;
+//!
+//! let data = "1002  Advanced Micro Devices, Inc. [AMD/ATI]\n\t67df  Ellesmere\n";
+//! let mut parser = Parser::new(Cursor::new(data));
+//!
+//! while let Some(event) = parser.next_event().unwrap() {
+//!     if let Event::Device { id: 0x67df, name } = event {
+//!         assert_eq!(name, "Ellesmere");
+//!     }
+//! }
+//! # }
+//! ```
+use crate::{
+    error::{Error, ErrorKind, ParseError},
+    schema::Metadata,
+};
+use alloc::{borrow::ToOwned, vec::Vec};
+#[cfg(feature = "std")]
+use std::{io::BufRead, string::String};
 
 const SPLIT: &str = "  ";
 
-#[derive(Debug, PartialEq, Eq)]
+/// A single entry of the database.
+///
+/// Ids are parsed from hex, names are borrowed from the line that is currently being read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Event<'a> {
     Vendor {
-        id: &'a str,
+        id: u16,
         name: &'a str,
     },
     Device {
-        id: &'a str,
+        id: u16,
         name: &'a str,
     },
     Subdevice {
-        subvendor: &'a str,
-        subdevice: &'a str,
+        subvendor: u16,
+        subdevice: u16,
         subsystem_name: &'a str,
     },
     Class {
-        id: &'a str,
+        id: u8,
         name: &'a str,
     },
     SubClass {
-        id: &'a str,
+        id: u8,
         name: &'a str,
     },
     ProgIf {
-        id: &'a str,
+        id: u8,
         name: &'a str,
     },
 }
 
+impl<'a> Event<'a> {
+    /// The name of the entry
+    #[must_use]
+    pub fn name(&self) -> &'a str {
+        match self {
+            Event::Vendor { name, .. }
+            | Event::Device { name, .. }
+            | Event::Class { name, .. }
+            | Event::SubClass { name, .. }
+            | Event::ProgIf { name, .. } => name,
+            Event::Subdevice { subsystem_name, .. } => subsystem_name,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn with_name(self, name: &str) -> Event<'_> {
+        match self {
+            Event::Vendor { id, .. } => Event::Vendor { id, name },
+            Event::Device { id, .. } => Event::Device { id, name },
+            Event::Subdevice {
+                subvendor,
+                subdevice,
+                ..
+            } => Event::Subdevice {
+                subvendor,
+                subdevice,
+                subsystem_name: name,
+            },
+            Event::Class { id, .. } => Event::Class { id, name },
+            Event::SubClass { id, .. } => Event::SubClass { id, name },
+            Event::ProgIf { id, .. } => Event::ProgIf { id, name },
+        }
+    }
+}
+
+/// A malformed line that was skipped by a lenient parser
+pub type Warning = ParseError;
+
+/// Options controlling how malformed input is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Fail on the first malformed line.
+    /// When disabled, malformed lines and entries nested under them are skipped and recorded as warnings.
+    pub strict: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self { strict: true }
+    }
+}
+
+impl ParseOptions {
+    /// Options that skip malformed lines instead of failing
+    #[must_use]
+    pub fn lenient() -> Self {
+        Self { strict: false }
+    }
+}
+
+/// Pull-parser over a `pci.ids` file.
+///
+/// Events borrow from the parser's internal line buffer, so they need to be consumed
+/// before [`Parser::next_event`] is called again.
+#[cfg(feature = "std")]
 pub struct Parser<R> {
     reader: R,
     buf: String,
-    section: Section,
+    lines: LineParser,
 }
 
+/// Pull-parser over a `pci.ids` file that is already in memory.
+///
+/// Unlike [`Parser`], the events borrow from the input itself, so they can be kept around
+/// while parsing continues. This is what [`BorrowedDatabase`](crate::BorrowedDatabase) is built on.
+pub struct StrParser<'a> {
+    input: &'a str,
+    lines: LineParser,
+}
+
+/// Line handling that is shared between the parsers
+pub(crate) struct LineParser {
+    state: State,
+    options: ParseOptions,
+    pub(crate) warnings: Vec<Warning>,
+    pub(crate) metadata: Metadata,
+    line: usize,
+    offset: u64,
+}
+
+#[derive(Default)]
 enum Section {
+    #[default]
     Devices,
     Classes,
 }
 
+/// How deep the following lines can be nested
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Nesting {
+    #[default]
+    None,
+    /// Below a vendor or a class
+    Entry,
+    /// Below a device or a subclass
+    SubEntry,
+}
+
+#[derive(Default)]
+struct State {
+    section: Section,
+    nesting: Nesting,
+}
+
+#[cfg(feature = "std")]
 impl<R: BufRead> Parser<R> {
-    pub(crate) fn new(reader: R) -> Self {
+    /// Create a strict parser over the given reader
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// Create a parser over the given reader with custom options
+    pub fn with_options(reader: R, options: ParseOptions) -> Self {
         Self {
             reader,
             buf: String::new(),
-            section: Section::Devices,
+            lines: LineParser::new(options),
         }
     }
 
-    pub fn next_event(&mut self) -> Result<Option<Event>, Error> {
-        self.buf.clear();
+    /// Malformed lines that have been skipped so far. Always empty in strict mode.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.lines.warnings
+    }
+
+    /// Information from the header comments that have been read so far
+    pub fn metadata(&self) -> &Metadata {
+        &self.lines.metadata
+    }
 
-        while self.reader.read_line(&mut self.buf)? != 0 {
-            if self.buf.is_empty() || self.buf.starts_with('#') || self.buf == "\n" {
-                self.buf.clear();
-                continue;
+    /// Take the collected warnings out of the parser
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        core::mem::take(&mut self.lines.warnings)
+    }
+
+    /// Read the next entry from the database, skipping comments and empty lines.
+    /// Returns `None` once the end of the input has been reached.
+    ///
+    /// # Errors
+    /// Returns an error when reading fails or, in strict mode, when a line is malformed
+    pub fn next_event(&mut self) -> Result<Option<Event<'_>>, Error> {
+        let (event, name_start) = loop {
+            self.buf.clear();
+            if self.reader.read_line(&mut self.buf)? == 0 {
+                return Ok(None);
             }
 
-            let buf = &self.buf[..self.buf.len() - 1];
+            if let Some(event) = self.lines.parse(&self.buf)? {
+                // The name always spans until the end of the line.
+                // The event is detached from the buffer here so that the loop can keep reading.
+                let line = trim_line_end(&self.buf);
+                break (event.with_name(""), line.len() - event.name().len());
+            }
+        };
 
-            let event = if let Some(buf) = buf.strip_prefix("C ") {
-                self.section = Section::Classes;
+        let line = trim_line_end(&self.buf);
+        Ok(Some(event.with_name(&line[name_start..])))
+    }
+}
+
+impl<'a> StrParser<'a> {
+    /// Create a strict parser over the given input
+    #[must_use]
+    pub fn new(input: &'a str) -> Self {
+        Self::with_options(input, ParseOptions::default())
+    }
+
+    /// Create a parser over the given input with custom options
+    #[must_use]
+    pub fn with_options(input: &'a str, options: ParseOptions) -> Self {
+        Self {
+            input,
+            lines: LineParser::new(options),
+        }
+    }
+
+    /// Create a strict parser over raw bytes, which are checked to be valid UTF-8 up front
+    ///
+    /// # Errors
+    /// Returns an error when the input is not valid UTF-8
+    pub fn from_bytes(input: &'a [u8]) -> Result<Self, Error> {
+        let input = core::str::from_utf8(input).map_err(Error::InvalidUtf8)?;
+        Ok(Self::new(input))
+    }
 
-                let (id, name) = parse_split(buf)?;
-                Event::Class { id, name }
-            } else if let Some(buf) = buf.strip_prefix("\t\t") {
-                // Subdevice
-                let (prefix, name) = parse_split(buf)?;
+    /// Malformed lines that have been skipped so far. Always empty in strict mode.
+    #[must_use]
+    pub fn warnings(&self) -> &[Warning] {
+        &self.lines.warnings
+    }
+
+    /// Information from the header comments that have been read so far
+    #[must_use]
+    pub fn metadata(&self) -> &Metadata {
+        &self.lines.metadata
+    }
+
+    /// Take the collected warnings out of the parser
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        core::mem::take(&mut self.lines.warnings)
+    }
+
+    /// Read the next entry from the database, skipping comments and empty lines.
+    /// Returns `None` once the end of the input has been reached.
+    ///
+    /// # Errors
+    /// Returns an error in strict mode when a line is malformed
+    pub fn next_event(&mut self) -> Result<Option<Event<'a>>, Error> {
+        while !self.input.is_empty() {
+            let line_end = self
+                .input
+                .find('\n')
+                .map_or(self.input.len(), |pos| pos + 1);
+            let (line, rest) = self.input.split_at(line_end);
+            self.input = rest;
+
+            if let Some(event) = self.lines.parse(line)? {
+                return Ok(Some(event));
+            }
+        }
 
-                if let Some((subvendor, subdevice)) = prefix.split_once(' ') {
-                    Event::Subdevice {
-                        subvendor,
-                        subdevice,
+        Ok(None)
+    }
+}
+
+impl<'a> Iterator for StrParser<'a> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+impl LineParser {
+    pub(crate) fn new(options: ParseOptions) -> Self {
+        Self {
+            state: State::default(),
+            options,
+            warnings: Vec::new(),
+            metadata: Metadata::default(),
+            line: 0,
+            offset: 0,
+        }
+    }
+
+    /// Handle a single line, including its terminator.
+    /// Returns `None` for comments, empty lines and lines that were skipped in lenient mode.
+    pub(crate) fn parse<'l>(&mut self, raw_line: &'l str) -> Result<Option<Event<'l>>, Error> {
+        let line_offset = self.offset;
+        self.line += 1;
+        self.offset += raw_line.len() as u64;
+
+        let line = trim_line_end(raw_line);
+        if is_skipped(line) {
+            if self.state.in_header() {
+                self.metadata.parse_header_line(line);
+            }
+            return Ok(None);
+        }
+
+        match self.state.parse_line(line) {
+            Ok(event) => Ok(Some(event)),
+            Err(kind) => {
+                let error = ParseError {
+                    kind,
+                    line: self.line,
+                    offset: line_offset,
+                    content: line.to_owned(),
+                };
+                if self.options.strict {
+                    return Err(Error::Parse(error));
+                }
+                self.warnings.push(error);
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl State {
+    /// Whether no entries have been read yet
+    fn in_header(&self) -> bool {
+        matches!(self.section, Section::Devices) && self.nesting == Nesting::None
+    }
+
+    /// Parse a single non-empty line.
+    ///
+    /// The nesting is lowered before validating a line, so that when a line is malformed
+    /// the entries below it are reported as orphans instead of being attached to the previous entry.
+    fn parse_line<'a>(&mut self, line: &'a str) -> Result<Event<'a>, ErrorKind> {
+        if let Some(line) = line.strip_prefix("C ") {
+            self.section = Section::Classes;
+            self.nesting = Nesting::None;
+
+            let (id, name) = parse_split(line)?;
+            let id = parse_u8(id)?;
+
+            self.nesting = Nesting::Entry;
+            Ok(Event::Class { id, name })
+        } else if let Some(line) = line.strip_prefix("\t\t") {
+            let (prefix, name) = parse_split(line)?;
+
+            match self.section {
+                Section::Devices => {
+                    if self.nesting < Nesting::SubEntry {
+                        return Err(ErrorKind::OrphanSubdevice);
+                    }
+                    let (subvendor, subdevice) =
+                        prefix.split_once(' ').ok_or(ErrorKind::MissingDelimiter)?;
+
+                    Ok(Event::Subdevice {
+                        subvendor: parse_u16(subvendor)?,
+                        subdevice: parse_u16(subdevice)?,
                         subsystem_name: name,
+                    })
+                }
+                Section::Classes => {
+                    if self.nesting < Nesting::SubEntry {
+                        return Err(ErrorKind::OrphanProgIf);
                     }
-                } else {
-                    Event::ProgIf { id: prefix, name }
+                    Ok(Event::ProgIf {
+                        id: parse_u8(prefix)?,
+                        name,
+                    })
                 }
-            } else if let Some(buf) = buf.strip_prefix('\t') {
-                let (id, name) = parse_split(buf)?;
+            }
+        } else if let Some(line) = line.strip_prefix('\t') {
+            let orphan = self.nesting < Nesting::Entry;
+            self.nesting = self.nesting.min(Nesting::Entry);
 
-                match self.section {
-                    Section::Devices => Event::Device { id, name },
-                    Section::Classes => Event::SubClass { id, name },
+            let (id, name) = parse_split(line)?;
+
+            match self.section {
+                Section::Devices => {
+                    if orphan {
+                        return Err(ErrorKind::OrphanDevice);
+                    }
+                    let id = parse_u16(id)?;
+                    self.nesting = Nesting::SubEntry;
+                    Ok(Event::Device { id, name })
                 }
-            } else {
-                let (id, name) = parse_split(buf)?;
-                Event::Vendor { id, name }
-            };
-            return Ok(Some(event));
-        }
+                Section::Classes => {
+                    if orphan {
+                        return Err(ErrorKind::OrphanSubclass);
+                    }
+                    let id = parse_u8(id)?;
+                    self.nesting = Nesting::SubEntry;
+                    Ok(Event::SubClass { id, name })
+                }
+            }
+        } else {
+            self.nesting = Nesting::None;
 
-        Ok(None)
+            let (id, name) = parse_split(line)?;
+            let id = parse_u16(id)?;
+
+            self.nesting = Nesting::Entry;
+            Ok(Event::Vendor { id, name })
+        }
     }
 }
 
-fn parse_split(buf: &str) -> Result<(&str, &str), Error> {
-    buf.split_once(SPLIT)
-        .ok_or_else(|| Error::Parse(format!("missing delimiter in line {buf}")))
+fn is_skipped(line: &str) -> bool {
+    line.is_empty() || line.starts_with('#')
+}
+
+pub(crate) fn trim_line_end(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
 }
 
-#[cfg(test)]
+fn parse_split(line: &str) -> Result<(&str, &str), ErrorKind> {
+    line.split_once(SPLIT).ok_or(ErrorKind::MissingDelimiter)
+}
+
+fn parse_u16(value: &str) -> Result<u16, ErrorKind> {
+    u16::from_str_radix(value, 16).map_err(|_| ErrorKind::InvalidHexId)
+}
+
+fn parse_u8(value: &str) -> Result<u8, ErrorKind> {
+    u8::from_str_radix(value, 16).map_err(|_| ErrorKind::InvalidHexId)
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use super::Parser;
-    use crate::parser::{Event, Section};
+    use super::{Parser, StrParser};
+    use crate::{
+        error::{Error, ErrorKind, ParseError},
+        parser::{Event, Nesting, Section},
+    };
     use pretty_assertions::assert_eq;
     use std::{
         fs::File,
@@ -121,34 +473,144 @@ mod tests {
 
         assert_eq!(
             Event::Vendor {
-                id: "0001",
+                id: 0x0001,
                 name: "SafeNet (wrong ID)"
             },
             parser.next_event().unwrap().unwrap()
         );
         assert_eq!(
             Event::Vendor {
-                id: "0010",
+                id: 0x0010,
                 name: "Allied Telesis, Inc (Wrong ID)"
             },
             parser.next_event().unwrap().unwrap()
         );
         assert_eq!(
             Event::Device {
-                id: "8139",
+                id: 0x8139,
                 name: "AT-2500TX V3 Ethernet"
             },
             parser.next_event().unwrap().unwrap()
         );
     }
 
+    #[test]
+    fn parse_subdevice_line() {
+        let buf = "1002  Advanced Micro Devices, Inc. [AMD/ATI]\n\t67df  Ellesmere\n\t\t1da2 e387  Radeon RX 580 Pulse 4GB\n";
+        let mut parser = Parser::new(Cursor::new(buf));
+        parser.next_event().unwrap();
+        parser.next_event().unwrap();
+        assert_eq!(
+            Event::Subdevice {
+                subvendor: 0x1da2,
+                subdevice: 0xe387,
+                subsystem_name: "Radeon RX 580 Pulse 4GB"
+            },
+            parser.next_event().unwrap().unwrap()
+        );
+        assert_eq!(None, parser.next_event().unwrap());
+    }
+
+    #[test]
+    fn invalid_hex_id() {
+        let buf = "# comment\n\n10g2  Broken vendor\n";
+        let mut parser = Parser::new(Cursor::new(buf));
+        let Err(Error::Parse(err)) = parser.next_event() else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            ParseError {
+                kind: ErrorKind::InvalidHexId,
+                line: 3,
+                offset: 11,
+                content: "10g2  Broken vendor".to_owned(),
+            },
+            err
+        );
+    }
+
+    #[test]
+    fn missing_delimiter() {
+        let buf = "1002  Advanced Micro Devices, Inc. [AMD/ATI]\n\t67df Ellesmere\n";
+        let mut parser = Parser::new(Cursor::new(buf));
+        parser.next_event().unwrap();
+        let Err(Error::Parse(err)) = parser.next_event() else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(ErrorKind::MissingDelimiter, err.kind);
+        assert_eq!(2, err.line);
+        assert_eq!(45, err.offset);
+        assert_eq!("\t67df Ellesmere", err.content);
+    }
+
+    #[test]
+    fn orphan_entries() {
+        let cases = [
+            ("\t67df  Ellesmere\n", ErrorKind::OrphanDevice),
+            (
+                "1002  AMD\n\t\t1da2 e387  Radeon RX 580 Pulse 4GB\n",
+                ErrorKind::OrphanSubdevice,
+            ),
+            (
+                "C 03  Display controller\n\t\t00  VGA controller\n",
+                ErrorKind::OrphanProgIf,
+            ),
+        ];
+
+        for (buf, kind) in cases {
+            let mut parser = Parser::new(Cursor::new(buf));
+            let err = loop {
+                match parser.next_event() {
+                    Ok(Some(_)) => (),
+                    Ok(None) => panic!("expected a parse error"),
+                    Err(Error::Parse(err)) => break err,
+                    Err(err) => panic!("unexpected error {err}"),
+                }
+            };
+            assert_eq!(kind, err.kind);
+        }
+    }
+
+    #[test]
+    fn str_parser_matches_reader() {
+        let data = std::fs::read_to_string("./tests/pci.ids").unwrap();
+        let mut parser = Parser::new(Cursor::new(&data));
+        let mut str_parser = StrParser::new(&data);
+
+        while let Some(event) = parser.next_event().unwrap() {
+            assert_eq!(Some(event), str_parser.next_event().unwrap());
+        }
+        assert_eq!(None, str_parser.next_event().unwrap());
+        assert_eq!(parser.metadata(), str_parser.metadata());
+    }
+
+    #[test]
+    fn str_parser_without_trailing_newline() {
+        let events: Vec<_> = StrParser::new("1002  AMD\r\n\t67df  Ellesmere")
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            events,
+            [
+                Event::Vendor {
+                    id: 0x1002,
+                    name: "AMD"
+                },
+                Event::Device {
+                    id: 0x67df,
+                    name: "Ellesmere"
+                }
+            ]
+        );
+    }
+
     #[test]
     fn parse_class_line() {
         let mut parser = Parser::new(Cursor::new("C 00  Unclassified device\n"));
-        parser.section = Section::Classes;
+        parser.lines.state.section = Section::Classes;
         assert_eq!(
             Event::Class {
-                id: "00",
+                id: 0x00,
                 name: "Unclassified device"
             },
             parser.next_event().unwrap().unwrap()
@@ -159,10 +621,11 @@ mod tests {
     fn parse_subclass_line() {
         let buf = "	01  IDE interface\n";
         let mut parser = Parser::new(Cursor::new(buf));
-        parser.section = Section::Classes;
+        parser.lines.state.section = Section::Classes;
+        parser.lines.state.nesting = Nesting::Entry;
         assert_eq!(
             Event::SubClass {
-                id: "01",
+                id: 0x01,
                 name: "IDE interface"
             },
             parser.next_event().unwrap().unwrap()
@@ -173,10 +636,11 @@ mod tests {
     fn parse_prog_if_line() {
         let buf = "		00  ISA Compatibility mode-only controller\n";
         let mut parser = Parser::new(Cursor::new(buf));
-        parser.section = Section::Classes;
+        parser.lines.state.section = Section::Classes;
+        parser.lines.state.nesting = Nesting::SubEntry;
         assert_eq!(
             Event::ProgIf {
-                id: "00",
+                id: 0x00,
                 name: "ISA Compatibility mode-only controller"
             },
             parser.next_event().unwrap().unwrap()
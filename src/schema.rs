@@ -1,6 +1,10 @@
+use alloc::{collections::BTreeMap, string::String};
+use core::{
+    fmt::{self, Display},
+    hash::Hash,
+};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, hash::Hash};
 
 #[derive(Default, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -11,37 +15,171 @@ pub struct DeviceInfo<'a> {
     pub subdevice_name: Option<&'a str>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClassInfo<'a> {
+    pub class_name: Option<&'a str>,
+    pub subclass_name: Option<&'a str>,
+    pub prog_if_name: Option<&'a str>,
+}
+
+/// A vendor and its devices.
+///
+/// Names are owned `String`s by default, or `&str` slices of the input in a [`BorrowedDatabase`](crate::BorrowedDatabase).
+#[derive(Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Vendor {
-    pub name: String,
-    pub devices: HashMap<u16, Device>,
+pub struct Vendor<S = String> {
+    pub name: S,
+    pub devices: BTreeMap<u16, Device<S>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Device {
-    pub name: String,
-    pub subdevices: HashMap<SubDeviceId, String>,
+pub struct Device<S = String> {
+    pub name: S,
+    pub subdevices: BTreeMap<SubDeviceId, S>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SubDeviceId {
     pub subvendor: u16,
     pub subdevice: u16,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct Class {
-    pub name: String,
-    pub subclasses: HashMap<u8, SubClass>,
+pub struct Class<S = String> {
+    pub name: S,
+    pub subclasses: BTreeMap<u8, SubClass<S>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct SubClass {
-    pub name: String,
-    pub prog_ifs: HashMap<u8, String>,
+pub struct SubClass<S = String> {
+    pub name: S,
+    pub prog_ifs: BTreeMap<u8, S>,
+}
+
+/// The full id path of a single entry in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EntryId {
+    Vendor(u16),
+    Device {
+        vendor: u16,
+        device: u16,
+    },
+    Subdevice {
+        vendor: u16,
+        device: u16,
+        subvendor: u16,
+        subdevice: u16,
+    },
+    Class(u8),
+    SubClass {
+        class: u8,
+        subclass: u8,
+    },
+    ProgIf {
+        class: u8,
+        subclass: u8,
+        prog_if: u8,
+    },
+}
+
+/// Information from the header of the database file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Metadata {
+    pub version: Option<Version>,
+    pub date: Option<Timestamp>,
+}
+
+/// Database version in the `YYYY.MM.DD` format used by the PCI ID project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Version {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// Date and time of the database snapshot, in `YYYY-MM-DD HH:MM:SS` format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Timestamp {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl Metadata {
+    /// Fill in the metadata from a header comment line such as `# Version: 2023.09.22`
+    pub(crate) fn parse_header_line(&mut self, line: &str) {
+        let Some(line) = line.strip_prefix('#') else {
+            return;
+        };
+        let line = line.trim_start();
+
+        if let Some(value) = line.strip_prefix("Version:") {
+            self.version = Version::parse(value.trim());
+        } else if let Some(value) = line.strip_prefix("Date:") {
+            self.date = Timestamp::parse(value.trim());
+        }
+    }
+}
+
+impl Version {
+    /// Parse a version in the `YYYY.MM.DD` format
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split('.');
+        let version = Self {
+            year: parts.next()?.parse().ok()?,
+            month: parts.next()?.parse().ok()?,
+            day: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(version)
+    }
+}
+
+impl Timestamp {
+    /// Parse a timestamp in the `YYYY-MM-DD HH:MM:SS` format
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let (date, time) = value.split_once(' ')?;
+
+        let mut date = date.split('-');
+        let mut time = time.trim_start().split(':');
+
+        let timestamp = Self {
+            year: date.next()?.parse().ok()?,
+            month: date.next()?.parse().ok()?,
+            day: date.next()?.parse().ok()?,
+            hour: time.next()?.parse().ok()?,
+            minute: time.next()?.parse().ok()?,
+            second: time.next()?.parse().ok()?,
+        };
+        (date.next().is_none() && time.next().is_none()).then_some(timestamp)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}.{:02}.{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
 }
@@ -0,0 +1,228 @@
+//! Finding entries by their names.
+//!
+//! ```rust
+//! use pciid_parser::{schema::EntryId, Database};
+//!
+//! let db: Database = "1002  Advanced Micro Devices, Inc. [AMD/ATI]\n\t73bf  Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]\n"
+//!     .parse()
+//!     .unwrap();
+//!
+//! let ids: Vec<_> = db.search("navi 21").map(|found| found.entry).collect();
+//! assert_eq!(ids, [EntryId::Device { vendor: 0x1002, device: 0x73bf }]);
+//! ```
+use crate::{name::ParsedName, schema::EntryId, Database};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+/// An entry whose name matches a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a> {
+    /// The kind of the entry along with its full id path
+    pub entry: EntryId,
+    pub name: &'a str,
+}
+
+impl<S: AsRef<str>> Database<S> {
+    /// Find all entries whose names contain the query, ignoring case.
+    ///
+    /// Vendors are visited in id order with their devices and subsystems, followed by the classes.
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = Match<'a>> + 'a {
+        let query = query.to_lowercase();
+        self.search_by(move |name| contains_ignore_case(name, &query))
+    }
+
+    /// Find all entries whose names match the regular expression.
+    /// Use the `(?i)` flag for a case-insensitive search.
+    #[cfg(feature = "regex")]
+    pub fn search_regex<'a>(
+        &'a self,
+        regex: &'a regex::Regex,
+    ) -> impl Iterator<Item = Match<'a>> + 'a {
+        self.search_by(|name| regex.is_match(name))
+    }
+
+    /// Find all entries whose names satisfy the predicate
+    pub fn search_by<'a, F>(&'a self, mut predicate: F) -> impl Iterator<Item = Match<'a>> + 'a
+    where
+        F: FnMut(&str) -> bool + 'a,
+    {
+        self.entries().filter(move |found| predicate(found.name))
+    }
+
+    /// All entries of the database, in the same order as [`Database::search`] visits them
+    pub fn entries(&self) -> impl Iterator<Item = Match<'_>> {
+        let devices = self.vendors.iter().flat_map(|(&vendor, vendor_entry)| {
+            let devices = vendor_entry
+                .devices
+                .iter()
+                .flat_map(move |(&device, device_entry)| {
+                    let subdevices = device_entry.subdevices.iter().map(move |(id, name)| Match {
+                        entry: EntryId::Subdevice {
+                            vendor,
+                            device,
+                            subvendor: id.subvendor,
+                            subdevice: id.subdevice,
+                        },
+                        name: name.as_ref(),
+                    });
+
+                    let device = Match {
+                        entry: EntryId::Device { vendor, device },
+                        name: device_entry.name.as_ref(),
+                    };
+                    core::iter::once(device).chain(subdevices)
+                });
+
+            let vendor = Match {
+                entry: EntryId::Vendor(vendor),
+                name: vendor_entry.name.as_ref(),
+            };
+            core::iter::once(vendor).chain(devices)
+        });
+
+        let classes = self.classes.iter().flat_map(|(&class, class_entry)| {
+            let subclasses =
+                class_entry
+                    .subclasses
+                    .iter()
+                    .flat_map(move |(&subclass, subclass_entry)| {
+                        let prog_ifs =
+                            subclass_entry
+                                .prog_ifs
+                                .iter()
+                                .map(move |(&prog_if, name)| Match {
+                                    entry: EntryId::ProgIf {
+                                        class,
+                                        subclass,
+                                        prog_if,
+                                    },
+                                    name: name.as_ref(),
+                                });
+
+                        let subclass = Match {
+                            entry: EntryId::SubClass { class, subclass },
+                            name: subclass_entry.name.as_ref(),
+                        };
+                        core::iter::once(subclass).chain(prog_ifs)
+                    });
+
+            let class = Match {
+                entry: EntryId::Class(class),
+                name: class_entry.name.as_ref(),
+            };
+            core::iter::once(class).chain(subclasses)
+        });
+
+        devices.chain(classes)
+    }
+}
+
+/// A device or subsystem that is similar to a fuzzy query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch<'a> {
+    pub entry: EntryId,
+    /// The full name of the entry
+    pub name: &'a str,
+    /// The part of the name that matched best, either the name without its bracketed aliases
+    /// or a single alias such as `Radeon RX 580` from `Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]`
+    pub matched: Cow<'a, str>,
+    /// How well the query matched, higher is better
+    pub score: u32,
+}
+
+impl<S: AsRef<str>> Database<S> {
+    /// Rank devices and subsystems by how well their names match a query such as `rx 580` or `GTX 1080 Ti`.
+    ///
+    /// Every word of the query has to appear in the name or in one of its bracketed aliases,
+    /// either as a whole word, as the start of a word or inside a word. Ties are broken by
+    /// preferring shorter names, then by id. The best match comes first.
+    #[must_use]
+    pub fn fuzzy_search(&self, query: &str) -> Vec<FuzzyMatch<'_>> {
+        let query = tokenize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<_> = self
+            .entries()
+            .filter(|found| {
+                matches!(
+                    found.entry,
+                    EntryId::Device { .. } | EntryId::Subdevice { .. }
+                )
+            })
+            .filter_map(|found| {
+                let parsed = ParsedName::parse(found.name);
+                let (score, matched) = core::iter::once(Cow::Borrowed(parsed.base))
+                    .chain(parsed.variants)
+                    .filter_map(|candidate| Some((fuzzy_score(&query, &candidate)?, candidate)))
+                    .max_by_key(|(score, _)| *score)?;
+
+                Some(FuzzyMatch {
+                    entry: found.entry,
+                    name: found.name,
+                    matched,
+                    score,
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| b.score.cmp(&a.score).then(a.entry.cmp(&b.entry)));
+        matches
+    }
+}
+
+/// Score every word of the query against the candidate, or `None` if any of them is missing
+fn fuzzy_score(query: &[String], candidate: &str) -> Option<u32> {
+    const EXACT: u32 = 8;
+    const JOINED: u32 = 6;
+    const PREFIX: u32 = 4;
+    const INNER: u32 = 2;
+
+    let words = tokenize(candidate);
+    let mut points = 0;
+
+    for token in query {
+        let joined = words.windows(2).any(|pair| {
+            token.len() == pair[0].len() + pair[1].len()
+                && token.starts_with(&pair[0])
+                && token.ends_with(&pair[1])
+        });
+
+        points += if words.contains(token) {
+            EXACT
+        } else if joined {
+            // Such as `rx580` for `RX 580`
+            JOINED
+        } else if words.iter().any(|word| word.starts_with(token.as_str())) {
+            PREFIX
+        } else if words.iter().any(|word| word.contains(token.as_str())) {
+            INNER
+        } else {
+            return None;
+        };
+    }
+
+    // Words that the query doesn't mention make the match less specific
+    let extra_words = u32::try_from(words.len().saturating_sub(query.len())).unwrap_or(u32::MAX);
+    Some(points * 16 + 15 - extra_words.min(15))
+}
+
+/// Split into lowercase words, ignoring punctuation
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Check whether the haystack contains an already lowercased needle, without allocating
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+
+    haystack.char_indices().any(|(start, _)| {
+        let mut rest = haystack[start..].chars().flat_map(char::to_lowercase);
+        needle.chars().all(|expected| rest.next() == Some(expected))
+    })
+}
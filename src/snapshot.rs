@@ -0,0 +1,544 @@
+//! Compact binary snapshots of a database that can be loaded without parsing.
+//!
+//! A snapshot is a header followed by a body with sorted, fixed-size id tables and a string pool.
+//! Lookups binary search the tables in place, so loading only has to read and verify the file.
+//!
+//! All integers are little-endian. The layout is:
+//!
+//! | Field                  | Size                                        |
+//! |------------------------|---------------------------------------------|
+//! | magic `PCIIDSNP`       | 8                                           |
+//! | format version         | 4                                           |
+//! | FNV-1a checksum of body| 8                                           |
+//! | body length            | 8                                           |
+//! | source mtime           | 12 (seconds, nanoseconds, all ones if unknown) |
+//! | version                | 4 (zero if unknown)                         |
+//! | date                   | 8 (zero if unknown)                         |
+//! | table lengths          | 7 × 4 (six tables and the string pool)      |
+//! | tables                 | 20 bytes per record                         |
+//! | string pool            | UTF-8                                       |
+//!
+//! Every record is `key, name offset, name length, first child, child count`.
+//! Children of a record are stored contiguously in the next table, sorted by key.
+use crate::{
+    error::Error,
+    parser::Parser,
+    schema::{ClassInfo, DeviceInfo, Metadata, Timestamp, Version},
+    Database,
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    ops::Range,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const MAGIC: &[u8; 8] = b"PCIIDSNP";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 8 + 4 + 8 + 8;
+const METADATA_LEN: usize = 12 + 4 + 8;
+const TABLE_COUNT: usize = 6;
+const RECORD_LEN: usize = 20;
+
+#[derive(Clone, Copy)]
+enum Table {
+    Vendors,
+    Devices,
+    Subdevices,
+    Classes,
+    SubClasses,
+    ProgIfs,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Record {
+    key: u32,
+    name_offset: u32,
+    name_len: u32,
+    first_child: u32,
+    child_count: u32,
+}
+
+/// A database loaded from a binary snapshot
+#[derive(Debug)]
+pub struct Snapshot {
+    body: Vec<u8>,
+    metadata: Metadata,
+    source_mtime: Option<SystemTime>,
+    tables: [Range<usize>; TABLE_COUNT],
+    strings: Range<usize>,
+}
+
+impl<S: AsRef<str>> Database<S> {
+    /// Write the database as a binary snapshot that can be read with [`Database::load_snapshot`]
+    ///
+    /// # Errors
+    /// Returns an error when writing fails
+    pub fn save_snapshot<W: Write>(&self, writer: W) -> Result<(), Error> {
+        self.write_snapshot(writer, None)
+    }
+
+    fn write_snapshot<W: Write>(
+        &self,
+        writer: W,
+        source_mtime: Option<SystemTime>,
+    ) -> Result<(), Error> {
+        let mut tables: [Vec<Record>; TABLE_COUNT] = Default::default();
+        let mut strings = StringPool::default();
+
+        for (&vendor_id, vendor) in &self.vendors {
+            let mut devices = Vec::new();
+            for (&device_id, device) in &vendor.devices {
+                let record = strings.record(device.name.as_ref());
+                let children = device.subdevices.iter().map(|(id, name)| Record {
+                    key: u32::from(id.subvendor) << 16 | u32::from(id.subdevice),
+                    ..strings.record(name.as_ref())
+                });
+                devices.push(push_children(
+                    &mut tables[Table::Subdevices as usize],
+                    device_id.into(),
+                    record,
+                    children,
+                ));
+            }
+
+            let vendor = push_children(
+                &mut tables[Table::Devices as usize],
+                vendor_id.into(),
+                strings.record(vendor.name.as_ref()),
+                devices,
+            );
+            tables[Table::Vendors as usize].push(vendor);
+        }
+
+        for (&class_id, class) in &self.classes {
+            let mut subclasses = Vec::new();
+            for (&subclass_id, subclass) in &class.subclasses {
+                let record = strings.record(subclass.name.as_ref());
+                let children = subclass.prog_ifs.iter().map(|(&prog_if_id, name)| Record {
+                    key: prog_if_id.into(),
+                    ..strings.record(name.as_ref())
+                });
+                subclasses.push(push_children(
+                    &mut tables[Table::ProgIfs as usize],
+                    subclass_id.into(),
+                    record,
+                    children,
+                ));
+            }
+
+            let class = push_children(
+                &mut tables[Table::SubClasses as usize],
+                class_id.into(),
+                strings.record(class.name.as_ref()),
+                subclasses,
+            );
+            tables[Table::Classes as usize].push(class);
+        }
+
+        let mut body = Vec::new();
+        write_metadata(&mut body, &self.metadata, source_mtime);
+        for table in &tables {
+            body.extend_from_slice(&len_u32(table.len()).to_le_bytes());
+        }
+        body.extend_from_slice(&len_u32(strings.data.len()).to_le_bytes());
+        for record in tables.iter().flatten() {
+            for value in [
+                record.key,
+                record.name_offset,
+                record.name_len,
+                record.first_child,
+                record.child_count,
+            ] {
+                body.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        body.extend_from_slice(strings.data.as_bytes());
+
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&checksum(&body).to_le_bytes())?;
+        writer.write_all(&(body.len() as u64).to_le_bytes())?;
+        writer.write_all(&body)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Database {
+    /// Load a binary snapshot written by [`Database::save_snapshot`]
+    ///
+    /// # Errors
+    /// Returns an error when reading fails or the snapshot is invalid or corrupted
+    pub fn load_snapshot<R: Read>(mut reader: R) -> Result<Snapshot, Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Snapshot::from_bytes(data)
+    }
+
+    /// Load the snapshot at `snapshot_path` if it is up to date with the text database at `source_path`.
+    /// Otherwise the text database is parsed and the snapshot is rewritten for the next time.
+    ///
+    /// A snapshot is stale when the modification time or the `# Version` header of the source
+    /// don't match the ones it was created from. A valid snapshot is also used when the source can't be read.
+    /// Failing to write the snapshot is not an error, so this also works when the snapshot location is read-only.
+    ///
+    /// # Errors
+    /// Returns an error when the snapshot can't be used and the source can't be read or parsed
+    pub fn load_snapshot_or_parse<P, Q>(snapshot_path: P, source_path: Q) -> Result<Snapshot, Error>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let snapshot_path = snapshot_path.as_ref();
+        let source_path = source_path.as_ref();
+
+        let cached = File::open(snapshot_path)
+            .map_err(Error::from)
+            .and_then(Self::load_snapshot);
+        if let Ok(snapshot) = cached {
+            if !snapshot.is_stale(source_path).unwrap_or(false) {
+                return Ok(snapshot);
+            }
+        }
+
+        let source_mtime = fs::metadata(source_path)?.modified().ok();
+        let db = Self::read_from_file(source_path)?;
+
+        let mut data = Vec::new();
+        db.write_snapshot(&mut data, source_mtime)?;
+        let _ = crate::write_atomically(snapshot_path, &data);
+
+        Snapshot::from_bytes(data)
+    }
+}
+
+impl Snapshot {
+    /// Verify and load a snapshot from its bytes
+    ///
+    /// # Errors
+    /// Returns an error when the snapshot is invalid or corrupted
+    pub fn from_bytes(mut data: Vec<u8>) -> Result<Self, Error> {
+        let header = data
+            .get(..HEADER_LEN)
+            .ok_or(Error::InvalidSnapshot("file is too short"))?;
+        if &header[..8] != MAGIC {
+            return Err(Error::InvalidSnapshot("not a snapshot"));
+        }
+        if read_u32(header, 8) != Some(FORMAT_VERSION) {
+            return Err(Error::InvalidSnapshot("unsupported format version"));
+        }
+        let expected_checksum = read_u64(header, 12);
+        let body_len = read_u64(header, 20);
+        if body_len != Some((data.len() - HEADER_LEN) as u64) {
+            return Err(Error::InvalidSnapshot("unexpected length"));
+        }
+
+        let body = data.split_off(HEADER_LEN);
+        if Some(checksum(&body)) != expected_checksum {
+            return Err(Error::InvalidSnapshot("checksum mismatch"));
+        }
+
+        let (metadata, source_mtime) = read_metadata(&body);
+
+        let mut offset = METADATA_LEN + (TABLE_COUNT + 1) * 4;
+        let mut tables: [Range<usize>; TABLE_COUNT] = Default::default();
+        for (index, table) in tables.iter_mut().enumerate() {
+            let len = read_u32(&body, METADATA_LEN + index * 4)
+                .ok_or(Error::InvalidSnapshot("file is too short"))?;
+            let end = usize::try_from(len)
+                .ok()
+                .and_then(|len| len.checked_mul(RECORD_LEN))
+                .and_then(|size| offset.checked_add(size))
+                .ok_or(Error::InvalidSnapshot("malformed tables"))?;
+            *table = offset..end;
+            offset = end;
+        }
+        let strings_len = read_u32(&body, METADATA_LEN + TABLE_COUNT * 4)
+            .ok_or(Error::InvalidSnapshot("file is too short"))?;
+        let strings_end = usize::try_from(strings_len)
+            .ok()
+            .and_then(|len| offset.checked_add(len))
+            .ok_or(Error::InvalidSnapshot("malformed tables"))?;
+        let strings = offset..strings_end;
+
+        if strings.end != body.len() || std::str::from_utf8(&body[strings.clone()]).is_err() {
+            return Err(Error::InvalidSnapshot("malformed tables"));
+        }
+
+        Ok(Self {
+            body,
+            metadata,
+            source_mtime,
+            tables,
+            strings,
+        })
+    }
+
+    /// Information from the header of the database the snapshot was created from
+    #[must_use]
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Modification time of the text database the snapshot was created from, if known
+    #[must_use]
+    pub fn source_mtime(&self) -> Option<SystemTime> {
+        self.source_mtime
+    }
+
+    /// Check whether the text database at the given path has changed since the snapshot was created
+    ///
+    /// # Errors
+    /// Returns an error when the source can't be read
+    pub fn is_stale<P: AsRef<Path>>(&self, source_path: P) -> Result<bool, Error> {
+        let source_path = source_path.as_ref();
+
+        let mtime = fs::metadata(source_path)?.modified().ok();
+        if mtime.is_none() || mtime != self.source_mtime {
+            return Ok(true);
+        }
+
+        // The version is in the header, so parsing can stop at the first entry
+        let file = crate::compression::decompress(File::open(source_path)?)?;
+        let mut parser = Parser::new(BufReader::new(file));
+        parser.next_event()?;
+        Ok(parser.metadata().version != self.metadata.version)
+    }
+
+    #[must_use]
+    pub fn get_device_info(
+        &self,
+        vendor_id: u16,
+        model_id: u16,
+        subsys_vendor_id: u16,
+        subsys_model_id: u16,
+    ) -> DeviceInfo<'_> {
+        let mut info = DeviceInfo::default();
+
+        if let Some(vendor) = self.find_top(Table::Vendors, vendor_id.into()) {
+            info.vendor_name = self.name(vendor);
+
+            if let Some(device) = self.find_child(Table::Devices, vendor, model_id.into()) {
+                info.device_name = self.name(device);
+                info.subvendor_name = self
+                    .find_top(Table::Vendors, subsys_vendor_id.into())
+                    .and_then(|subvendor| self.name(subvendor));
+
+                let key = u32::from(subsys_vendor_id) << 16 | u32::from(subsys_model_id);
+                info.subdevice_name = self
+                    .find_child(Table::Subdevices, device, key)
+                    .and_then(|subdevice| self.name(subdevice));
+            }
+        }
+
+        info
+    }
+
+    /// Look up the names of a class, subclass and programming interface
+    #[must_use]
+    pub fn get_class_info(&self, class_id: u8, subclass_id: u8, prog_if_id: u8) -> ClassInfo<'_> {
+        let mut info = ClassInfo::default();
+
+        if let Some(class) = self.find_top(Table::Classes, class_id.into()) {
+            info.class_name = self.name(class);
+
+            if let Some(subclass) = self.find_child(Table::SubClasses, class, subclass_id.into()) {
+                info.subclass_name = self.name(subclass);
+                info.prog_if_name = self
+                    .find_child(Table::ProgIfs, subclass, prog_if_id.into())
+                    .and_then(|prog_if| self.name(prog_if));
+            }
+        }
+
+        info
+    }
+
+    /// Look up the class names from a packed 24-bit class code.
+    /// All names are empty when the code doesn't fit in 24 bits.
+    #[must_use]
+    pub fn get_class_info_by_code(&self, class_code: u32) -> ClassInfo<'_> {
+        let [0, class_id, subclass_id, prog_if_id] = class_code.to_be_bytes() else {
+            return ClassInfo::default();
+        };
+        self.get_class_info(class_id, subclass_id, prog_if_id)
+    }
+
+    fn find_top(&self, table: Table, key: u32) -> Option<Record> {
+        let len = self.tables[table as usize].len() / RECORD_LEN;
+        self.find(table, 0..len, key)
+    }
+
+    fn find_child(&self, table: Table, parent: Record, key: u32) -> Option<Record> {
+        let start = parent.first_child as usize;
+        self.find(table, start..start + parent.child_count as usize, key)
+    }
+
+    /// Binary search for a key within a range of records
+    fn find(&self, table: Table, records: Range<usize>, key: u32) -> Option<Record> {
+        let (mut low, mut high) = (records.start, records.end);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let record = self.record(table, middle)?;
+            match record.key.cmp(&key) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Some(record),
+            }
+        }
+        None
+    }
+
+    fn record(&self, table: Table, index: usize) -> Option<Record> {
+        let range = &self.tables[table as usize];
+        let start = range.start + index * RECORD_LEN;
+        if start + RECORD_LEN > range.end {
+            return None;
+        }
+
+        Some(Record {
+            key: read_u32(&self.body, start)?,
+            name_offset: read_u32(&self.body, start + 4)?,
+            name_len: read_u32(&self.body, start + 8)?,
+            first_child: read_u32(&self.body, start + 12)?,
+            child_count: read_u32(&self.body, start + 16)?,
+        })
+    }
+
+    fn name(&self, record: Record) -> Option<&str> {
+        let strings = self.body.get(self.strings.clone())?;
+        let start = record.name_offset as usize;
+        let bytes = strings.get(start..start + record.name_len as usize)?;
+        std::str::from_utf8(bytes).ok()
+    }
+}
+
+/// Deduplicated storage for the names
+#[derive(Default)]
+struct StringPool<'a> {
+    data: String,
+    offsets: HashMap<&'a str, u32>,
+}
+
+impl<'a> StringPool<'a> {
+    fn record(&mut self, name: &'a str) -> Record {
+        let data = &mut self.data;
+        let name_offset = *self.offsets.entry(name).or_insert_with(|| {
+            let offset = len_u32(data.len());
+            data.push_str(name);
+            offset
+        });
+
+        Record {
+            name_offset,
+            name_len: len_u32(name.len()),
+            ..Record::default()
+        }
+    }
+}
+
+/// Append the children to their table and return the parent record pointing at them
+fn push_children(
+    table: &mut Vec<Record>,
+    key: u32,
+    record: Record,
+    children: impl IntoIterator<Item = Record>,
+) -> Record {
+    let first_child = len_u32(table.len());
+    table.extend(children);
+
+    Record {
+        key,
+        first_child,
+        child_count: len_u32(table.len()) - first_child,
+        ..record
+    }
+}
+
+fn write_metadata(body: &mut Vec<u8>, metadata: &Metadata, source_mtime: Option<SystemTime>) {
+    let mtime = source_mtime.and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok());
+    let (secs, nanos) = mtime.map_or((u64::MAX, u32::MAX), |mtime| {
+        (mtime.as_secs(), mtime.subsec_nanos())
+    });
+    body.extend_from_slice(&secs.to_le_bytes());
+    body.extend_from_slice(&nanos.to_le_bytes());
+
+    let version = metadata.version.unwrap_or(Version {
+        year: 0,
+        month: 0,
+        day: 0,
+    });
+    body.extend_from_slice(&version.year.to_le_bytes());
+    body.extend_from_slice(&[version.month, version.day]);
+
+    let date = metadata.date.unwrap_or(Timestamp {
+        year: 0,
+        month: 0,
+        day: 0,
+        hour: 0,
+        minute: 0,
+        second: 0,
+    });
+    body.extend_from_slice(&date.year.to_le_bytes());
+    body.extend_from_slice(&[date.month, date.day, date.hour, date.minute, date.second, 0]);
+}
+
+fn read_metadata(body: &[u8]) -> (Metadata, Option<SystemTime>) {
+    let bytes = |range: Range<usize>| body.get(range).unwrap_or_default();
+
+    let source_mtime = match (read_u64(body, 0), read_u32(body, 8)) {
+        (Some(u64::MAX) | None, _) | (_, None) => None,
+        (Some(secs), Some(nanos)) => UNIX_EPOCH.checked_add(Duration::new(secs, nanos)),
+    };
+
+    let version = match (read_u16(body, 12), bytes(14..16)) {
+        (Some(year), &[month, day]) if year != 0 => Some(Version { year, month, day }),
+        _ => None,
+    };
+    let date = match (read_u16(body, 16), bytes(18..23)) {
+        (Some(year), &[month, day, hour, minute, second]) if year != 0 => Some(Timestamp {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }),
+        _ => None,
+    };
+
+    (Metadata { version, date }, source_mtime)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// The database is far smaller than 4 GiB, so lengths always fit
+fn len_u32(len: usize) -> u32 {
+    u32::try_from(len).expect("snapshot section is too large")
+}
+
+/// 64-bit FNV-1a
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,174 @@
+//! Enumeration of PCI devices through sysfs.
+//!
+//! ```rust,no_run
+//! use pciid_parser::{sysfs, Database};
+//!
+//! let db = Database::read().unwrap();
+//! for device in sysfs::devices(&db).unwrap() {
+//!     println!("{} {:?}", device.address, device.device_info.device_name);
+//! }
+//! ```
+use crate::{
+    error::Error,
+    schema::{ClassInfo, DeviceInfo},
+    Database,
+};
+use std::{
+    fmt::{self, Display},
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+/// Where the kernel lists PCI devices
+pub const DEFAULT_ROOT: &str = "/sys/bus/pci/devices";
+
+/// Location of a device on the PCI bus, in the `domain:bus:device.function` form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PciAddress {
+    pub domain: u32,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+}
+
+/// A device found in sysfs along with its names from the database
+#[derive(Debug, Clone)]
+pub struct PciDevice<'a> {
+    pub address: PciAddress,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Zero when the device doesn't report a subsystem
+    pub subsystem_vendor_id: u16,
+    /// Zero when the device doesn't report a subsystem
+    pub subsystem_device_id: u16,
+    /// Packed 24-bit class code
+    pub class_code: u32,
+    pub revision: u8,
+    pub device_info: DeviceInfo<'a>,
+    pub class_info: ClassInfo<'a>,
+}
+
+impl PciAddress {
+    /// Parse an address such as `0000:03:00.0`
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let (domain, rest) = value.split_once(':')?;
+        let (bus, rest) = rest.split_once(':')?;
+        let (device, function) = rest.split_once('.')?;
+
+        Some(Self {
+            domain: u32::from_str_radix(domain, 16).ok()?,
+            bus: u8::from_str_radix(bus, 16).ok()?,
+            device: u8::from_str_radix(device, 16).ok()?,
+            function: u8::from_str_radix(function, 16).ok()?,
+        })
+    }
+}
+
+impl Display for PciAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04x}:{:02x}:{:02x}.{:x}",
+            self.domain, self.bus, self.device, self.function
+        )
+    }
+}
+
+/// List the devices in [`DEFAULT_ROOT`], sorted by address
+///
+/// # Errors
+/// Returns an error when the device attributes can't be read or are malformed
+pub fn devices<S: AsRef<str>>(db: &Database<S>) -> Result<Vec<PciDevice<'_>>, Error> {
+    devices_at(db, DEFAULT_ROOT)
+}
+
+/// List the devices in a directory laid out like `/sys/bus/pci/devices`, sorted by address.
+/// Entries that are not named like a PCI address are ignored.
+///
+/// # Errors
+/// Returns an error when the directory or the device attributes can't be read or are malformed
+pub fn devices_at<S: AsRef<str>, P: AsRef<Path>>(
+    db: &Database<S>,
+    root: P,
+) -> Result<Vec<PciDevice<'_>>, Error> {
+    let mut devices = Vec::new();
+
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        let Some(address) = entry.file_name().to_str().and_then(PciAddress::parse) else {
+            continue;
+        };
+        let path = entry.path();
+
+        let vendor_id = read_u16(&path, "vendor")?;
+        let device_id = read_u16(&path, "device")?;
+        let subsystem_vendor_id = read_optional(&path, "subsystem_vendor", read_u16)?;
+        let subsystem_device_id = read_optional(&path, "subsystem_device", read_u16)?;
+        let class_code = read_hex(&path, "class")?;
+        let revision = read_optional(&path, "revision", read_u8)?;
+
+        devices.push(PciDevice {
+            address,
+            vendor_id,
+            device_id,
+            subsystem_vendor_id,
+            subsystem_device_id,
+            class_code,
+            revision,
+            device_info: db.get_device_info(
+                vendor_id,
+                device_id,
+                subsystem_vendor_id,
+                subsystem_device_id,
+            ),
+            class_info: db.get_class_info_by_code(class_code),
+        });
+    }
+
+    devices.sort_unstable_by_key(|device| device.address);
+    Ok(devices)
+}
+
+/// Read an attribute such as `0x1002`
+fn read_hex(dir: &Path, name: &str) -> Result<u32, Error> {
+    let path = dir.join(name);
+    let contents = fs::read_to_string(&path)?;
+    let value = contents.trim();
+
+    value
+        .strip_prefix("0x")
+        .and_then(|value| u32::from_str_radix(value, 16).ok())
+        .ok_or_else(|| {
+            let message = format!("invalid value {value:?} in {}", path.display());
+            Error::Io(io::Error::new(ErrorKind::InvalidData, message))
+        })
+}
+
+fn read_u16(dir: &Path, name: &str) -> Result<u16, Error> {
+    let value = read_hex(dir, name)?;
+    u16::try_from(value).map_err(|_| invalid_range(dir, name))
+}
+
+fn read_u8(dir: &Path, name: &str) -> Result<u8, Error> {
+    let value = read_hex(dir, name)?;
+    u8::try_from(value).map_err(|_| invalid_range(dir, name))
+}
+
+/// Read an attribute that not all devices have, defaulting to zero
+fn read_optional<T: Default>(
+    dir: &Path,
+    name: &str,
+    read: fn(&Path, &str) -> Result<T, Error>,
+) -> Result<T, Error> {
+    match read(dir, name) {
+        Err(Error::Io(err)) if err.kind() == ErrorKind::NotFound => Ok(T::default()),
+        result => result,
+    }
+}
+
+fn invalid_range(dir: &Path, name: &str) -> Error {
+    let message = format!("value out of range in {}", dir.join(name).display());
+    Error::Io(io::Error::new(ErrorKind::InvalidData, message))
+}
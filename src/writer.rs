@@ -0,0 +1,60 @@
+use crate::{error::Error, Database};
+use std::io::{BufWriter, Write};
+
+impl<S: AsRef<str>> Database<S> {
+    /// Write the database in the `pci.ids` format, with all entries sorted by id.
+    /// The output can be read back with [`Database::parse_db`] or by `lspci -i`.
+    ///
+    /// # Errors
+    /// Returns an error when writing fails
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+
+        writeln!(writer, "#\n#\tList of PCI ID's\n#")?;
+        if let Some(version) = &self.metadata.version {
+            writeln!(writer, "#\tVersion: {version}")?;
+        }
+        if let Some(date) = &self.metadata.date {
+            writeln!(writer, "#\tDate:    {date}")?;
+        }
+        writeln!(writer, "#")?;
+
+        writeln!(writer, "\n# Vendors, devices and subsystems.\n")?;
+        for (vendor_id, vendor) in &self.vendors {
+            writeln!(writer, "{vendor_id:04x}  {}", vendor.name.as_ref())?;
+
+            for (device_id, device) in &vendor.devices {
+                writeln!(writer, "\t{device_id:04x}  {}", device.name.as_ref())?;
+
+                for (id, name) in &device.subdevices {
+                    writeln!(
+                        writer,
+                        "\t\t{:04x} {:04x}  {}",
+                        id.subvendor,
+                        id.subdevice,
+                        name.as_ref()
+                    )?;
+                }
+            }
+        }
+
+        writeln!(
+            writer,
+            "\n# Device classes, subclasses and programming interfaces.\n"
+        )?;
+        for (class_id, class) in &self.classes {
+            writeln!(writer, "C {class_id:02x}  {}", class.name.as_ref())?;
+
+            for (subclass_id, subclass) in &class.subclasses {
+                writeln!(writer, "\t{subclass_id:02x}  {}", subclass.name.as_ref())?;
+
+                for (prog_if_id, name) in &subclass.prog_ifs {
+                    writeln!(writer, "\t\t{prog_if_id:02x}  {}", name.as_ref())?;
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
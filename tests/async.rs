@@ -0,0 +1,88 @@
+#![cfg(feature = "async")]
+use pciid_parser::{asynchronous::AsyncParser, parser::Event, Database, ErrorKind, ParseOptions};
+use pretty_assertions::assert_eq;
+use std::{future::Future, io::Cursor};
+
+const DB_DATA: &str = include_str!("pci.ids");
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn parse_same_as_sync() {
+    let db = block_on(Database::parse_db_async(Cursor::new(DB_DATA))).unwrap();
+    assert_eq!(db, Database::parse_db(Cursor::new(DB_DATA)).unwrap());
+}
+
+#[test]
+fn read_from_file() {
+    let db = block_on(Database::read_from_file_async("tests/pci.ids")).unwrap();
+    assert_eq!(db, Database::read_from_file("tests/pci.ids").unwrap());
+}
+
+#[test]
+fn read_default_path() {
+    let db = block_on(Database::read_async()).unwrap();
+    assert_eq!(db, Database::read().unwrap());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn read_gzip_file() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(DB_DATA.as_bytes()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pci.ids.gz");
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+    let db = block_on(Database::read_from_file_async(path)).unwrap();
+    assert_eq!(db, Database::parse_db(Cursor::new(DB_DATA)).unwrap());
+}
+
+#[test]
+fn lenient_warnings() {
+    let data = "1002  AMD\n\t67zz  Broken\n\t67df  Ellesmere\n";
+    let (db, warnings) = block_on(Database::parse_db_async_with_options(
+        Cursor::new(data),
+        ParseOptions::lenient(),
+    ))
+    .unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, ErrorKind::InvalidHexId);
+    assert_eq!(warnings[0].line, 2);
+    assert_eq!(db.vendors[&0x1002].devices[&0x67df].name, "Ellesmere");
+}
+
+#[test]
+fn parser_events() {
+    let data = "#\tVersion: 2023.09.22\n1002  AMD\n\t67df  Ellesmere\r\n";
+
+    block_on(async {
+        let mut parser = AsyncParser::new(Cursor::new(data));
+        assert_eq!(
+            parser.next_event().await.unwrap(),
+            Some(Event::Vendor {
+                id: 0x1002,
+                name: "AMD"
+            })
+        );
+        assert_eq!(
+            parser.next_event().await.unwrap(),
+            Some(Event::Device {
+                id: 0x67df,
+                name: "Ellesmere"
+            })
+        );
+        assert_eq!(parser.next_event().await.unwrap(), None);
+        assert!(parser.metadata().version.is_some());
+    });
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "std")]
+use pciid_parser::{BorrowedDatabase, Database, Error, ParseOptions};
+use pretty_assertions::assert_eq;
+use std::io::Cursor;
+
+const DB_DATA: &str = include_str!("pci.ids");
+
+#[test]
+fn same_lookups_as_owned() {
+    let borrowed = BorrowedDatabase::parse_str(DB_DATA).unwrap();
+    let owned = Database::parse_db(Cursor::new(DB_DATA)).unwrap();
+
+    assert_eq!(borrowed.vendors.len(), owned.vendors.len());
+    assert_eq!(borrowed.classes.len(), owned.classes.len());
+    assert_eq!(borrowed.metadata, owned.metadata);
+
+    let info = borrowed.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(
+        info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+    assert_eq!(
+        borrowed.get_class_info_by_code(0x030000).subclass_name,
+        Some("VGA compatible controller")
+    );
+
+    for (vendor_id, vendor) in &owned.vendors {
+        let borrowed_vendor = &borrowed.vendors[vendor_id];
+        assert_eq!(borrowed_vendor.name, vendor.name);
+        assert_eq!(borrowed_vendor.devices.len(), vendor.devices.len());
+    }
+}
+
+#[test]
+fn names_point_into_input() {
+    let db = BorrowedDatabase::parse_str(DB_DATA).unwrap();
+    let name = db.vendors[&0x1002].name;
+
+    let input_range = DB_DATA.as_bytes().as_ptr_range();
+    assert!(input_range.contains(&name.as_ptr()));
+}
+
+#[test]
+fn write_borrowed() {
+    let db = BorrowedDatabase::parse_str(DB_DATA).unwrap();
+    let mut output = Vec::new();
+    db.write_to(&mut output).unwrap();
+
+    let parsed = Database::parse_db(Cursor::new(output)).unwrap();
+    assert_eq!(parsed, Database::parse_db(Cursor::new(DB_DATA)).unwrap());
+}
+
+#[test]
+fn lenient_borrowed() {
+    let (db, warnings) = BorrowedDatabase::parse_str_with_options(
+        "1002  AMD\n\t67zz  Broken\n\t67df  Ellesmere\n",
+        ParseOptions::lenient(),
+    )
+    .unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(db.vendors[&0x1002].devices[&0x67df].name, "Ellesmere");
+}
+
+#[test]
+fn parse_bytes() {
+    let db = BorrowedDatabase::parse_bytes(DB_DATA.as_bytes()).unwrap();
+    assert_eq!(db, BorrowedDatabase::parse_str(DB_DATA).unwrap());
+
+    let err = BorrowedDatabase::parse_bytes(b"1002  AMD \xff\n").unwrap_err();
+    assert!(matches!(err, Error::InvalidUtf8(_)));
+}
+
+#[test]
+fn owned_from_str() {
+    let db: Database = DB_DATA.parse().unwrap();
+    assert_eq!(db, Database::parse_db(Cursor::new(DB_DATA)).unwrap());
+}
@@ -1,3 +1,4 @@
+#![cfg(feature = "std")]
 use pciid_parser::Database;
 use pretty_assertions::assert_eq;
 
@@ -40,6 +41,46 @@ fn get_vga_controller_if_prog() {
     assert_eq!(prog_if_name, "VGA controller");
 }
 
+#[test]
+fn get_vga_class_info() {
+    let db = Database::read().unwrap();
+    let info = db.get_class_info(0x03, 0x00, 0x00);
+
+    assert_eq!(info.class_name, Some("Display controller"));
+    assert_eq!(info.subclass_name, Some("VGA compatible controller"));
+    assert_eq!(info.prog_if_name, Some("VGA controller"));
+}
+
+#[test]
+fn get_class_info_by_code() {
+    let db = Database::read().unwrap();
+    let info = db.get_class_info_by_code(0x0c_03_30);
+
+    assert_eq!(info.class_name, Some("Serial bus controller"));
+    assert_eq!(info.subclass_name, Some("USB controller"));
+    assert_eq!(info.prog_if_name, Some("XHCI"));
+}
+
+#[test]
+fn get_unknown_subclass_info() {
+    let db = Database::read().unwrap();
+    let info = db.get_class_info_by_code(0x03_7f_00);
+
+    assert_eq!(info.class_name, Some("Display controller"));
+    assert_eq!(info.subclass_name, None);
+    assert_eq!(info.prog_if_name, None);
+}
+
+#[test]
+fn get_class_info_out_of_range() {
+    let db = Database::read().unwrap();
+    let info = db.get_class_info_by_code(0x01_03_00_00);
+
+    assert_eq!(info.class_name, None);
+    assert_eq!(info.subclass_name, None);
+    assert_eq!(info.prog_if_name, None);
+}
+
 #[cfg(feature = "online")]
 #[test]
 fn get_usb_device_if_prog() {
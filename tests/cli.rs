@@ -0,0 +1,100 @@
+#![cfg(feature = "cli")]
+use pciid_parser::Database;
+use pretty_assertions::assert_eq;
+use std::process::{Command, Output};
+
+fn pciid(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pciid"))
+        .args(["--db", "tests/pci.ids"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn lookup() {
+    let output = pciid(&["lookup", "1002:67df:1da2:e387"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Vendor:    Advanced Micro Devices, Inc. [AMD/ATI]\n\
+         Device:    Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]\n\
+         Subvendor: Sapphire Technology Limited\n\
+         Subdevice: Radeon RX 580 Pulse 4GB\n"
+    );
+}
+
+#[test]
+fn lookup_unknown() {
+    let output = pciid(&["lookup", "1002:0001"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "Vendor:    Advanced Micro Devices, Inc. [AMD/ATI]\nDevice:    unknown\n"
+    );
+}
+
+#[test]
+fn invalid_ids() {
+    let output = pciid(&["lookup", "1002"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn class() {
+    let output = pciid(&["class", "0300"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("VGA compatible controller"));
+}
+
+#[test]
+fn search() {
+    let output = pciid(&["search", "rx 580 pulse"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("1002:67df:1da2:e387"));
+}
+
+#[test]
+fn modalias() {
+    let output = pciid(&[
+        "modalias",
+        "pci:v00001002d000067DFsv00001DA2sd0000E387bc03sc00i00",
+    ]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Class:     VGA compatible controller [0300]"));
+}
+
+#[test]
+fn info() {
+    let output = pciid(&["info"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("tests/pci.ids"));
+}
+
+#[test]
+fn convert_roundtrip() {
+    let output = pciid(&["convert", "--to", "ids"]);
+    assert!(output.status.success());
+
+    let converted: Database = stdout(&output).parse().unwrap();
+    assert_eq!(
+        converted,
+        Database::read_from_file("tests/pci.ids").unwrap()
+    );
+}
+
+#[test]
+fn convert_json() {
+    let output = pciid(&["convert", "--to", "json"]);
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["vendors"]["1002"]["devices"]["67df"]["name"],
+        "Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]"
+    );
+}
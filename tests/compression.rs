@@ -0,0 +1,112 @@
+#![cfg(feature = "std")]
+use pciid_parser::{compression::Compression, Database};
+use pretty_assertions::assert_eq;
+use std::{fs, io::Cursor, path::Path};
+
+const DB_DATA: &[u8] = include_bytes!("pci.ids");
+
+fn write_temp(name: &str, contents: &[u8]) -> (tempfile::TempDir, std::path::PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(name);
+    fs::write(&path, contents).unwrap();
+    (dir, path)
+}
+
+fn assert_same_as_plain(path: &Path) {
+    let db = Database::read_from_file(path).unwrap();
+    let plain = Database::parse_db(Cursor::new(DB_DATA)).unwrap();
+    assert_eq!(db, plain);
+}
+
+#[test]
+fn detect_magic() {
+    assert_eq!(
+        Compression::detect(&[0x1f, 0x8b, 0x08]),
+        Some(Compression::Gzip)
+    );
+    assert_eq!(
+        Compression::detect(b"\xfd7zXZ\x00\x00"),
+        Some(Compression::Xz)
+    );
+    assert_eq!(
+        Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]),
+        Some(Compression::Zstd)
+    );
+    assert_eq!(Compression::detect(b"BZh91AY&SY"), Some(Compression::Bzip2));
+    assert_eq!(Compression::detect(b"#\n#\tList of PCI ID's"), None);
+    assert_eq!(Compression::detect(b""), None);
+}
+
+#[test]
+fn read_uncompressed_file() {
+    let (_dir, path) = write_temp("pci.ids", DB_DATA);
+    assert_same_as_plain(&path);
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_feature_disabled() {
+    let (_dir, path) = write_temp("pci.ids.gz", &[0x1f, 0x8b, 0x08, 0x00]);
+    let err = Database::read_from_file(path).unwrap_err();
+    assert!(matches!(
+        err,
+        pciid_parser::Error::UnsupportedCompression(Compression::Gzip)
+    ));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn read_gzip_file() {
+    use flate2::{write::GzEncoder, Compression as Level};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+    encoder.write_all(DB_DATA).unwrap();
+    let (_dir, path) = write_temp("pci.ids.gz", &encoder.finish().unwrap());
+
+    assert_same_as_plain(&path);
+}
+
+#[cfg(feature = "xz")]
+#[test]
+fn read_xz_file() {
+    let mut compressed = Vec::new();
+    lzma_rs::xz_compress(&mut Cursor::new(DB_DATA), &mut compressed).unwrap();
+    let (_dir, path) = write_temp("pci.ids.xz", &compressed);
+
+    assert_same_as_plain(&path);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn read_zstd_file() {
+    use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+    let compressed = compress_to_vec(DB_DATA, CompressionLevel::Fastest);
+    let (_dir, path) = write_temp("pci.ids.zst", &compressed);
+
+    assert_same_as_plain(&path);
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn read_bzip2_file() {
+    use bzip2::{write::BzEncoder, Compression as Level};
+    use std::io::Write;
+
+    let mut encoder = BzEncoder::new(Vec::new(), Level::fast());
+    encoder.write_all(DB_DATA).unwrap();
+    let (_dir, path) = write_temp("pci.ids.bz2", &encoder.finish().unwrap());
+
+    assert_same_as_plain(&path);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn corrupted_zstd_file() {
+    let (_dir, path) = write_temp("pci.ids.zst", &[0x28, 0xb5, 0x2f, 0xfd, 0xff, 0xff]);
+    assert!(matches!(
+        Database::read_from_file(path),
+        Err(pciid_parser::Error::Io(_))
+    ));
+}
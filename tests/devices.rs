@@ -1,3 +1,4 @@
+#![cfg(feature = "std")]
 use pciid_parser::Database;
 use pretty_assertions::assert_eq;
 
@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+use pciid_parser::{
+    display::{self, Options},
+    sysfs::{PciAddress, PciDevice},
+    Database,
+};
+use pretty_assertions::assert_eq;
+
+fn device(db: &Database, ids: (u16, u16, u16, u16), class_code: u32) -> PciDevice<'_> {
+    let (vendor_id, device_id, subsystem_vendor_id, subsystem_device_id) = ids;
+    PciDevice {
+        address: PciAddress::parse("0000:03:00.0").unwrap(),
+        vendor_id,
+        device_id,
+        subsystem_vendor_id,
+        subsystem_device_id,
+        class_code,
+        revision: 0xe7,
+        device_info: db.get_device_info(
+            vendor_id,
+            device_id,
+            subsystem_vendor_id,
+            subsystem_device_id,
+        ),
+        class_info: db.get_class_info_by_code(class_code),
+    }
+}
+
+#[test]
+fn lspci_line() {
+    let db = Database::read().unwrap();
+    let device = device(&db, (0x1002, 0x67df, 0x1da2, 0xe387), 0x03_00_00);
+
+    assert_eq!(
+        display::line(&device, Options::default()),
+        "03:00.0 VGA compatible controller: Advanced Micro Devices, Inc. [AMD/ATI] \
+         Ellesmere [Radeon RX 470/480/570/570X/580/580X/590] (rev e7)"
+    );
+    assert_eq!(
+        display::line(&device, Options::numeric()),
+        "03:00.0 VGA compatible controller [0300]: Advanced Micro Devices, Inc. [AMD/ATI] \
+         Ellesmere [Radeon RX 470/480/570/570X/580/580X/590] [1002:67df] (rev e7)"
+    );
+    assert_eq!(
+        display::line(
+            &device,
+            Options {
+                numeric: false,
+                verbose: true
+            }
+        ),
+        "03:00.0 VGA compatible controller: Advanced Micro Devices, Inc. [AMD/ATI] \
+         Ellesmere [Radeon RX 470/480/570/570X/580/580X/590] (rev e7) (prog-if 00 [VGA controller])"
+    );
+    assert_eq!(
+        display::subsystem_line(&device, Options::default()).as_deref(),
+        Some("Subsystem: Sapphire Technology Limited Radeon RX 580 Pulse 4GB")
+    );
+    assert_eq!(
+        display::subsystem_line(&device, Options::numeric()).as_deref(),
+        Some("Subsystem: Sapphire Technology Limited Radeon RX 580 Pulse 4GB [1da2:e387]")
+    );
+}
+
+#[test]
+fn lspci_fallbacks() {
+    let db = Database::read().unwrap();
+
+    let mut unknown = device(&db, (0x1002, 0x0001, 0, 0), 0x03_7f_00);
+    unknown.revision = 0;
+    unknown.address.domain = 1;
+    assert_eq!(
+        display::line(&unknown, Options::default()),
+        "0001:03:00.0 Display controller [037f]: Advanced Micro Devices, Inc. [AMD/ATI] Device 0001"
+    );
+    assert_eq!(display::subsystem_line(&unknown, Options::default()), None);
+
+    let unknown = device(&db, (0x0002, 0x67df, 0, 0), 0x7f_00_00);
+    assert_eq!(
+        display::line(&unknown, Options::default()),
+        "03:00.0 Class 7f00: Device 0002:67df (rev e7)"
+    );
+    assert_eq!(
+        display::line(&unknown, Options::numeric()),
+        "03:00.0 Class [7f00]: Device [0002:67df] (rev e7)"
+    );
+}
+
+#[test]
+fn generic_subsystem() {
+    let db = Database::read().unwrap();
+    let device = device(&db, (0x1002, 0x67df, 0x1002, 0x67df), 0x03_00_00);
+
+    assert_eq!(
+        display::subsystem_line(&device, Options::default()).as_deref(),
+        Some(
+            "Subsystem: Advanced Micro Devices, Inc. [AMD/ATI] \
+             Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]"
+        )
+    );
+
+    let device = PciDevice {
+        subsystem_device_id: 0x0001,
+        ..device
+    };
+    assert_eq!(
+        display::subsystem_line(&device, Options::default()).as_deref(),
+        Some("Subsystem: Advanced Micro Devices, Inc. [AMD/ATI] Device 0001")
+    );
+}
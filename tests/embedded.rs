@@ -0,0 +1,51 @@
+#![cfg(feature = "embedded")]
+use pciid_parser::{embedded::EMBEDDED, schema::EntryId, Database};
+use pretty_assertions::assert_eq;
+
+// The embedded database is built from the same file unless `PCIIDS_EMBED_PATH` is set
+const DB_DATA: &str = include_str!("pci.ids");
+
+#[test]
+fn embedded_matches_parsed() {
+    // Parsed from a string so that the test also runs without the `std` feature
+    let db: Database = DB_DATA.parse().unwrap();
+    assert_eq!(EMBEDDED.metadata, db.metadata);
+    assert_eq!(EMBEDDED.vendors.len(), db.vendors.len());
+    assert_eq!(EMBEDDED.classes.len(), db.classes.len());
+
+    for (vendor_id, vendor) in &db.vendors {
+        for (device_id, device) in &vendor.devices {
+            for (subdevice_id, name) in &device.subdevices {
+                let entry = EntryId::Subdevice {
+                    vendor: *vendor_id,
+                    device: *device_id,
+                    subvendor: subdevice_id.subvendor,
+                    subdevice: subdevice_id.subdevice,
+                };
+                assert_eq!(EMBEDDED.get_name(entry), Some(name.as_str()));
+            }
+        }
+    }
+}
+
+#[test]
+fn embedded_lookups() {
+    let info = EMBEDDED.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(
+        info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(info.subvendor_name, Some("Sapphire Technology Limited"));
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+
+    let info = EMBEDDED.get_class_info_by_code(0x03_00_00);
+    assert_eq!(info.class_name, Some("Display controller"));
+    assert_eq!(info.subclass_name, Some("VGA compatible controller"));
+    assert_eq!(info.prog_if_name, Some("VGA controller"));
+    assert_eq!(
+        EMBEDDED.get_class_info_by_code(0x01_03_00_00).class_name,
+        None
+    );
+
+    assert_eq!(EMBEDDED.get_name(EntryId::Vendor(0x0002)), None);
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "std")]
+use pciid_parser::Database;
+use pretty_assertions::assert_eq;
+
+/// The global database is shared by the whole test binary, so everything is checked in a single test
+#[test]
+fn init_with_test_database() {
+    let db: Database = "1002  Test Vendor\n\t67df  Test Device\n\t\t1da2 e387  Test Subdevice\n"
+        .parse()
+        .unwrap();
+    pciid_parser::init_with(db).unwrap();
+
+    let global = pciid_parser::global();
+    assert!(std::ptr::eq(global, pciid_parser::try_global().unwrap()));
+    assert_eq!(global.vendors.len(), 1);
+
+    // The lookups use the shared database instead of reading the file
+    assert_eq!(
+        pciid_parser::find_vendor_name(0x1002).unwrap().as_deref(),
+        Some("Test Vendor")
+    );
+    assert_eq!(
+        pciid_parser::find_device_name(0x1002, 0x67df)
+            .unwrap()
+            .as_deref(),
+        Some("Test Device")
+    );
+    assert_eq!(
+        pciid_parser::find_subdevice_name(0x1002, 0x67df, 0x1da2, 0xe387)
+            .unwrap()
+            .as_deref(),
+        Some("Test Subdevice")
+    );
+    assert_eq!(pciid_parser::find_vendor_name(0x10de).unwrap(), None);
+
+    let other = Database::read().unwrap();
+    let rejected = pciid_parser::init_with(other).unwrap_err();
+    assert!(rejected.vendors.len() > 1);
+    assert_eq!(pciid_parser::global().vendors.len(), 1);
+}
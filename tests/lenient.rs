@@ -0,0 +1,56 @@
+#![cfg(feature = "std")]
+use pciid_parser::{Database, Error, ErrorKind, ParseOptions};
+use pretty_assertions::assert_eq;
+use std::io::Cursor;
+
+const BROKEN_DB: &str = "\
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+	67df  Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]
+		1da2 e387  Radeon RX 580 Pulse 4GB
+	67zz  Broken device
+		1da2 e388  Subdevice of a broken device
+	687f Vega 10 XL/XT [Radeon RX Vega 56/64]
+1043  ASUSTeK Computer Inc.
+	0555  Device
+1da2  Sapphire Technology Limited
+C 03  Display controller
+	00  VGA compatible controller
+		00  VGA controller
+";
+
+#[test]
+fn strict_by_default() {
+    let err = Database::parse_db(Cursor::new(BROKEN_DB)).unwrap_err();
+    let Error::Parse(err) = err else {
+        panic!("expected a parse error, got {err}");
+    };
+    assert_eq!(err.kind, ErrorKind::InvalidHexId);
+    assert_eq!(err.line, 4);
+}
+
+#[test]
+fn lenient_skips_broken_lines() {
+    let (db, warnings) =
+        Database::parse_db_with_options(Cursor::new(BROKEN_DB), ParseOptions::lenient()).unwrap();
+
+    let kinds: Vec<_> = warnings
+        .iter()
+        .map(|warning| (warning.line, warning.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (4, ErrorKind::InvalidHexId),
+            (5, ErrorKind::OrphanSubdevice),
+            (6, ErrorKind::MissingDelimiter),
+        ]
+    );
+
+    let amd = db.vendors.get(&0x1002).unwrap();
+    assert_eq!(amd.devices.len(), 1);
+
+    let info = db.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+    assert_eq!(db.vendors.len(), 3);
+    assert_eq!(db.classes.get(&0x03).unwrap().name, "Display controller");
+}
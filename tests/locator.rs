@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+use pciid_parser::{locator::DatabaseLocator, Database, Error};
+use pretty_assertions::assert_eq;
+use std::{collections::HashMap, fs, path::PathBuf};
+
+const DB_DATA: &str = include_str!("pci.ids");
+
+fn write_db(dir: &tempfile::TempDir, name: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, DB_DATA).unwrap();
+    path
+}
+
+#[test]
+fn reports_used_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_db(&dir, "custom.ids");
+
+    let (db, used_path) = DatabaseLocator::new()
+        .without_env()
+        .without_default_paths()
+        .path(dir.path().join("missing.ids"))
+        .path(&path)
+        .read()
+        .unwrap();
+
+    assert_eq!(used_path, path);
+    assert_eq!(db, Database::read_from_file(&path).unwrap());
+}
+
+#[test]
+fn not_found_lists_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = [dir.path().join("a.ids"), dir.path().join("b.ids")];
+
+    let err = DatabaseLocator::new()
+        .without_env()
+        .without_default_paths()
+        .path(&missing[0])
+        .path(&missing[1])
+        .locate()
+        .unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.contains(&missing[0].display().to_string()));
+    assert!(message.contains(&missing[1].display().to_string()));
+    assert!(matches!(err, Error::FileNotFound(paths) if paths == missing));
+}
+
+#[test]
+fn default_candidates() {
+    let candidates = DatabaseLocator::new()
+        .without_env()
+        .path("/opt/pci.ids")
+        .candidates();
+
+    assert_eq!(candidates[0], PathBuf::from("/opt/pci.ids"));
+    assert_eq!(
+        candidates[1],
+        PathBuf::from("/usr/local/share/hwdata/pci.ids")
+    );
+    assert!(candidates.contains(&PathBuf::from("/usr/share/hwdata/pci.ids")));
+    assert!(candidates.contains(&PathBuf::from("/run/current-system/sw/share/pci.ids")));
+
+    let mut unique = candidates.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), candidates.len());
+
+    #[cfg(feature = "gzip")]
+    assert!(candidates.contains(&PathBuf::from("/usr/share/misc/pci.ids.gz")));
+}
+
+#[test]
+fn env_vars() {
+    let dir = tempfile::tempdir().unwrap();
+    let xdg_path = write_db(&dir, "xdg/misc/pci.ids");
+    let hwdata_path = write_db(&dir, "hwdata/pci.ids");
+    let override_path = write_db(&dir, "override.ids");
+    let missing = dir.path().join("missing.ids");
+
+    let locate = |vars: Vec<(&'static str, PathBuf)>| {
+        let vars: HashMap<_, _> = vars.into_iter().collect();
+        DatabaseLocator::new()
+            .env_lookup(move |name| vars.get(name).map(|value| value.clone().into_os_string()))
+            .locate()
+    };
+    let xdg = ("XDG_DATA_DIRS", dir.path().join("xdg"));
+    let hwdata = ("HWDATA_DIR", dir.path().join("hwdata"));
+
+    assert_eq!(locate(vec![xdg.clone()]).unwrap(), xdg_path);
+    assert_eq!(
+        locate(vec![xdg.clone(), hwdata.clone()]).unwrap(),
+        hwdata_path
+    );
+    assert_eq!(
+        locate(vec![
+            xdg.clone(),
+            hwdata.clone(),
+            ("PCIIDS_PATH", override_path.clone())
+        ])
+        .unwrap(),
+        override_path
+    );
+    assert!(matches!(
+        locate(vec![xdg, hwdata, ("PCIIDS_PATH", missing.clone())]),
+        Err(Error::FileNotFound(paths)) if paths == [missing]
+    ));
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "std")]
+use pciid_parser::{
+    schema::{Timestamp, Version},
+    Database,
+};
+use pretty_assertions::assert_eq;
+use std::{cmp::Ordering, fs::File, io::Cursor};
+
+fn test_db() -> Database {
+    Database::parse_db(File::open("./tests/pci.ids").unwrap()).unwrap()
+}
+
+#[test]
+fn parse_header() {
+    let db = test_db();
+
+    assert_eq!(
+        db.metadata.version,
+        Some(Version {
+            year: 2023,
+            month: 9,
+            day: 22
+        })
+    );
+    assert_eq!(
+        db.metadata.date,
+        Some(Timestamp {
+            year: 2023,
+            month: 9,
+            day: 22,
+            hour: 3,
+            minute: 15,
+            second: 2
+        })
+    );
+    assert_eq!(db.metadata.version.unwrap().to_string(), "2023.09.22");
+    assert_eq!(db.metadata.date.unwrap().to_string(), "2023-09-22 03:15:02");
+}
+
+#[test]
+fn compare_versions() {
+    let current = test_db();
+    let old = Database::parse_db(Cursor::new(
+        "#\tVersion: 2021.01.02\n#\tDate:    2021-01-02 03:15:02\n1002  AMD\n",
+    ))
+    .unwrap();
+    let unversioned = Database::parse_db(Cursor::new("1002  AMD\n")).unwrap();
+
+    assert_eq!(current.compare_version(&old), Some(Ordering::Greater));
+    assert_eq!(old.compare_version(&current), Some(Ordering::Less));
+    assert_eq!(current.compare_version(&test_db()), Some(Ordering::Equal));
+    assert_eq!(current.compare_version(&unversioned), None);
+}
+
+#[test]
+fn ignore_comments_after_header() {
+    let db = Database::parse_db(Cursor::new(
+        "#\tVersion: 2021.01.02\n1002  AMD\n#\tVersion: 2024.01.01\n",
+    ))
+    .unwrap();
+
+    assert_eq!(db.metadata.version.unwrap().to_string(), "2021.01.02");
+}
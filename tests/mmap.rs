@@ -0,0 +1,27 @@
+#![cfg(feature = "mmap")]
+use pciid_parser::Database;
+use pretty_assertions::assert_eq;
+use std::fs;
+
+#[test]
+fn open_mmap() {
+    let handle = unsafe { Database::open_mmap("./tests/pci.ids") }.unwrap();
+    let db = handle.database();
+
+    let info = db.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(
+        info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+    assert_eq!(db.metadata.version.unwrap().to_string(), "2023.09.22");
+}
+
+#[test]
+fn reject_compressed_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pci.ids.gz");
+    fs::write(&path, [0x1f, 0x8b, 0x08, 0x00]).unwrap();
+
+    assert!(unsafe { Database::open_mmap(path) }.is_err());
+}
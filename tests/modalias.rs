@@ -0,0 +1,57 @@
+#![cfg(feature = "std")]
+use pciid_parser::{modalias::Modalias, Database};
+use pretty_assertions::assert_eq;
+
+const POLARIS: &str = "pci:v00001002d000067DFsv00001DA2sd0000E387bc03sc00i00";
+
+#[test]
+fn parse_modalias() {
+    let modalias: Modalias = POLARIS.parse().unwrap();
+
+    assert_eq!(
+        modalias,
+        Modalias {
+            vendor_id: 0x1002,
+            device_id: 0x67df,
+            subsystem_vendor_id: 0x1da2,
+            subsystem_device_id: 0xe387,
+            class: 0x03,
+            subclass: 0x00,
+            prog_if: 0x00,
+        }
+    );
+    assert_eq!(modalias.to_string(), POLARIS);
+}
+
+#[test]
+fn reject_invalid_modalias() {
+    for modalias in [
+        "usb:v1D6Bp0002d0606dc09dsc00dp01ic09isc00ip00in00",
+        "pci:v00001002d000067DFsv00001DA2sd0000E387bc03sc00",
+        "pci:v00001002d000067DFsv00001DA2sd0000E387bc03sc00i00extra",
+        "pci:v*d*sv*sd*bc03sc00i*",
+        "pci:v00011002d000067DFsv00001DA2sd0000E387bc03sc00i00",
+    ] {
+        assert!(modalias.parse::<Modalias>().is_err(), "{modalias}");
+    }
+}
+
+#[test]
+fn lookup_modalias() {
+    let db = Database::read().unwrap();
+    let info = db.lookup_modalias(&POLARIS.parse().unwrap());
+
+    assert_eq!(
+        info.device_info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(
+        info.device_info.subdevice_name,
+        Some("Radeon RX 580 Pulse 4GB")
+    );
+    assert_eq!(info.class_info.class_name, Some("Display controller"));
+    assert_eq!(
+        info.class_info.subclass_name,
+        Some("VGA compatible controller")
+    );
+}
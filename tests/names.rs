@@ -0,0 +1,71 @@
+#![cfg(feature = "std")]
+use pciid_parser::{name::ParsedName, Database};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn parse_vendor_name() {
+    let db = Database::read().unwrap();
+    let name = db.vendors[&0x1002].parsed_name();
+
+    assert_eq!(name.base, "Advanced Micro Devices, Inc.");
+    assert_eq!(name.short, Some("AMD/ATI"));
+    assert_eq!(name.variants, ["AMD", "ATI"]);
+}
+
+#[test]
+fn parse_device_name() {
+    let db = Database::read().unwrap();
+    let name = db.vendors[&0x1002].devices[&0x67df].parsed_name();
+
+    assert_eq!(name.base, "Ellesmere");
+    assert_eq!(name.short, Some("Radeon RX 470/480/570/570X/580/580X/590"));
+    assert_eq!(
+        name.variants,
+        [
+            "Radeon RX 470",
+            "Radeon RX 480",
+            "Radeon RX 570",
+            "Radeon RX 570X",
+            "Radeon RX 580",
+            "Radeon RX 580X",
+            "Radeon RX 590",
+        ]
+    );
+}
+
+#[test]
+fn parse_variant_groups() {
+    let name = ParsedName::parse("Navi 10 [Radeon RX 5600 OEM/5600 XT / 5700/5700 XT]");
+    assert_eq!(
+        name.variants,
+        [
+            "Radeon RX 5600 OEM",
+            "Radeon RX 5600 XT",
+            "Radeon RX 5700",
+            "Radeon RX 5700 XT",
+        ]
+    );
+
+    let name = ParsedName::parse("Radeon RX 580");
+    assert_eq!(name.base, "Radeon RX 580");
+    assert_eq!(name.short, None);
+    assert!(name.variants.is_empty());
+}
+
+#[test]
+fn variant_of_subsystem() {
+    let db = Database::read().unwrap();
+    let device = &db.vendors[&0x1002].devices[&0x67df];
+    let info = db.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+
+    let name = device.parsed_name();
+    assert_eq!(
+        name.variant_in(info.subdevice_name.unwrap()),
+        Some("Radeon RX 580")
+    );
+    assert_eq!(
+        name.variant_in("RADEON RX 580X Nitro+"),
+        Some("Radeon RX 580X")
+    );
+    assert_eq!(name.variant_in("Radeon RX 5800"), None);
+}
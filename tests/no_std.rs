@@ -0,0 +1,78 @@
+//! Only uses the parts of the crate that are available without the `std` feature,
+//! so that `cargo test --no-default-features` covers them
+use pciid_parser::{
+    parser::{Event, StrParser},
+    BorrowedDatabase, Database, Error, ErrorKind,
+};
+use pretty_assertions::assert_eq;
+
+const DB_DATA: &str = include_str!("pci.ids");
+
+#[test]
+fn parse_str_lookups() {
+    let db = BorrowedDatabase::parse_str(DB_DATA).unwrap();
+
+    let info = db.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(
+        info.vendor_name,
+        Some("Advanced Micro Devices, Inc. [AMD/ATI]")
+    );
+    assert_eq!(
+        info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+
+    let class_info = db.get_class_info_by_code(0x03_00_00);
+    assert_eq!(class_info.subclass_name, Some("VGA compatible controller"));
+}
+
+#[test]
+fn parse_bytes_same_as_owned() {
+    let borrowed = BorrowedDatabase::parse_bytes(DB_DATA.as_bytes()).unwrap();
+    let owned: Database = DB_DATA.parse().unwrap();
+
+    assert_eq!(borrowed.vendors.len(), owned.vendors.len());
+    assert_eq!(borrowed.classes.len(), owned.classes.len());
+    assert_eq!(
+        borrowed.get_device_info(0x10de, 0x1b80, 0, 0).device_name,
+        owned.get_device_info(0x10de, 0x1b80, 0, 0).device_name
+    );
+}
+
+#[test]
+fn str_parser_events() {
+    let mut parser = StrParser::new("1002  AMD\n\t67df  Ellesmere\n\t\t1da2 e387  Pulse\n");
+
+    assert_eq!(
+        parser.next_event().unwrap(),
+        Some(Event::Vendor {
+            id: 0x1002,
+            name: "AMD"
+        })
+    );
+    assert_eq!(
+        parser.next_event().unwrap(),
+        Some(Event::Device {
+            id: 0x67df,
+            name: "Ellesmere"
+        })
+    );
+    assert_eq!(
+        parser.next_event().unwrap(),
+        Some(Event::Subdevice {
+            subvendor: 0x1da2,
+            subdevice: 0xe387,
+            subsystem_name: "Pulse"
+        })
+    );
+    assert_eq!(parser.next_event().unwrap(), None);
+}
+
+#[test]
+fn parse_error() {
+    let err = BorrowedDatabase::parse_str("1002  AMD\n\t67dg  Broken\n").unwrap_err();
+    assert!(
+        matches!(err, Error::Parse(err) if err.kind == ErrorKind::InvalidHexId && err.line == 2)
+    );
+}
@@ -0,0 +1,408 @@
+#![cfg(feature = "online")]
+use pciid_parser::{online::OnlineSource, Database, Error};
+use pretty_assertions::assert_eq;
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+const DB_DATA: &str = include_str!("pci.ids");
+const ETAG: &str = "\"v1\"";
+const LAST_MODIFIED: &str = "Wed, 21 Oct 2026 07:28:00 GMT";
+
+/// A local stand-in for the online source, which records the headers of every request it receives
+struct Server {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl Server {
+    fn start(respond: fn(&str) -> Vec<u8>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = String::new();
+                let mut reader = BufReader::new(&stream);
+                // Read the headers up to the empty line that ends them
+                while reader.read_line(&mut request).unwrap() > 2 {}
+
+                let request = request.to_lowercase();
+                let response = respond(&request);
+                recorded.lock().unwrap().push(request);
+                // The client may have given up already
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn response(status: &str, headers: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+fn respond_with_validators(request: &str) -> Vec<u8> {
+    if request.contains(&format!("if-none-match: {}", ETAG.to_lowercase())) {
+        response("304 Not Modified", "", b"")
+    } else {
+        let headers = format!("ETag: {ETAG}\r\nLast-Modified: {LAST_MODIFIED}\r\n");
+        response("200 OK", &headers, DB_DATA.as_bytes())
+    }
+}
+
+fn expected() -> Database {
+    DB_DATA.parse().unwrap()
+}
+
+#[test]
+fn fetch_without_cache() {
+    let server = Server::start(|_| response("200 OK", "", DB_DATA.as_bytes()));
+    let source = OnlineSource::new().base_url(&server.url);
+    assert_eq!(source.urls(), [format!("{}/pci.ids", server.url)]);
+
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(source.fetch().unwrap(), expected());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].starts_with("get /pci.ids "));
+    assert!(requests[0].contains("user-agent: pciid-parser/"));
+    assert!(!requests[1].contains("if-none-match"));
+}
+
+#[test]
+fn not_modified_uses_cache() {
+    let server = Server::start(respond_with_validators);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .cache_dir(cache_dir.path().join("pciids"));
+
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(source.fetch().unwrap(), expected());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].contains("if-none-match"));
+    assert!(requests[1].contains(&format!("if-none-match: {}", ETAG.to_lowercase())));
+    assert!(requests[1].contains(&format!(
+        "if-modified-since: {}",
+        LAST_MODIFIED.to_lowercase()
+    )));
+}
+
+#[test]
+fn invalid_response_not_cached() {
+    let server = Server::start(|_| {
+        let headers = format!("ETag: {ETAG}\r\n");
+        response("200 OK", &headers, b"<html>Sign in to the network</html>\n")
+    });
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .cache_dir(cache_dir.path())
+        .max_age(Duration::from_secs(60 * 60));
+
+    assert!(matches!(source.fetch(), Err(Error::Parse(_))));
+    assert!(source.fetch().is_err());
+    assert_eq!(server.requests().len(), 2);
+    assert!(!server.requests()[1].contains("if-none-match"));
+    assert_eq!(fs::read_dir(cache_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn empty_response_tries_fallback() {
+    let empty = Server::start(|_| response("200 OK", &format!("ETag: {ETAG}\r\n"), b""));
+    let unexpected = Server::start(|_| response("304 Not Modified", "", b""));
+    let server = Server::start(|_| response("200 OK", "", DB_DATA.as_bytes()));
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&empty.url)
+        .fallback_base_url(&unexpected.url)
+        .cache_dir(cache_dir.path());
+
+    assert!(matches!(source.fetch(), Err(Error::Request(_))));
+    assert!(!cache_dir.path().join("pci.ids").exists());
+
+    let source = source
+        .fallback_base_url(&server.url)
+        .max_age(Duration::from_secs(60 * 60));
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(empty.requests().len(), 2);
+    assert_eq!(unexpected.requests().len(), 2);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn max_age_skips_request() {
+    let server = Server::start(respond_with_validators);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .cache_dir(cache_dir.path())
+        .max_age(Duration::from_secs(60 * 60));
+
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(server.requests().len(), 1);
+
+    let expired = source.max_age(Duration::ZERO);
+    assert_eq!(expired.fetch().unwrap(), expected());
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn cache_keyed_by_url() {
+    let first = Server::start(respond_with_validators);
+    let second = Server::start(respond_with_validators);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = |server: &Server| {
+        OnlineSource::new()
+            .base_url(&server.url)
+            .cache_dir(cache_dir.path())
+            .max_age(Duration::from_secs(60 * 60))
+    };
+
+    assert_eq!(source(&first).fetch().unwrap(), expected());
+    assert_eq!(source(&second).fetch().unwrap(), expected());
+    assert_eq!(source(&second).fetch().unwrap(), expected());
+    assert_eq!(second.requests().len(), 1);
+    assert!(!second.requests()[0].contains("if-none-match"));
+
+    // The cached file now comes from the second server
+    assert_eq!(source(&first).fetch().unwrap(), expected());
+    let requests = first.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[1].contains("if-none-match"));
+}
+
+#[test]
+fn fallback_gets_no_validators() {
+    let server = Server::start(respond_with_validators);
+    let broken = Server::start(|_| response("404 Not Found", "", b""));
+    let fallback = Server::start(respond_with_validators);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .cache_dir(cache_dir.path());
+    assert_eq!(source.fetch().unwrap(), expected());
+
+    let source = source
+        .base_url(&broken.url)
+        .fallback_base_url(&fallback.url);
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert!(!broken.requests()[0].contains("if-none-match"));
+    assert!(!fallback.requests()[0].contains("if-none-match"));
+}
+
+#[test]
+fn server_error() {
+    let server = Server::start(|_| response("500 Internal Server Error", "", b""));
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .cache_dir(cache_dir.path());
+
+    let err = source.fetch().unwrap_err();
+    assert!(matches!(err, Error::Request(_)));
+    assert!(!cache_dir.path().join("pci.ids").exists());
+}
+
+#[test]
+fn fallback_urls() {
+    let broken = Server::start(|_| response("404 Not Found", "", b""));
+    let server = Server::start(|_| response("200 OK", "", DB_DATA.as_bytes()));
+    let source = OnlineSource::new()
+        .base_url(format!("{}/mirror/", broken.url))
+        .fallback_base_url(&server.url)
+        .user_agent("mirror-sync");
+
+    assert_eq!(
+        source.urls(),
+        [
+            format!("{}/mirror/pci.ids", broken.url),
+            format!("{}/pci.ids", server.url)
+        ]
+    );
+    assert_eq!(Database::get_online_with(&source).unwrap(), expected());
+
+    let requests = broken.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("get /mirror/pci.ids "));
+    assert!(server.requests()[0].contains("user-agent: mirror-sync"));
+}
+
+#[test]
+fn all_urls_fail() {
+    let first = Server::start(|_| response("404 Not Found", "", b""));
+    let second = Server::start(|_| response("503 Service Unavailable", "", b""));
+    let source = OnlineSource::new()
+        .base_url(&first.url)
+        .fallback_base_url(&second.url);
+
+    let err = source.fetch().unwrap_err();
+    assert!(
+        matches!(err, Error::Request(ref err) if matches!(**err, ureq::Error::StatusCode(503)))
+    );
+}
+
+#[test]
+fn size_limit() {
+    let server = Server::start(|_| response("200 OK", "", DB_DATA.as_bytes()));
+    let source = OnlineSource::new().base_url(&server.url).size_limit(1024);
+
+    let err = source.fetch().unwrap_err();
+    assert!(matches!(err, Error::Request(_)));
+}
+
+#[test]
+fn read_timeout() {
+    let server = Server::start(|_| {
+        thread::sleep(Duration::from_secs(2));
+        response("200 OK", "", DB_DATA.as_bytes())
+    });
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .read_timeout(Duration::from_millis(100));
+
+    let err = source.fetch().unwrap_err();
+    assert!(matches!(err, Error::Request(_)));
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_download() {
+    use flate2::{write::GzEncoder, Compression as Level};
+    use pciid_parser::compression::Compression;
+
+    let server = Server::start(|request| {
+        if request.starts_with("get /pci.ids.gz ") {
+            let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+            encoder.write_all(DB_DATA.as_bytes()).unwrap();
+            response("200 OK", "", &encoder.finish().unwrap())
+        } else {
+            response("404 Not Found", "", b"")
+        }
+    });
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .compression(Compression::Gzip)
+        .cache_dir(cache_dir.path())
+        .max_age(Duration::from_secs(60 * 60));
+
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(source.fetch().unwrap(), expected());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn decompressed_size_limit() {
+    use flate2::{write::GzEncoder, Compression as Level};
+    use pciid_parser::compression::Compression;
+
+    let server = Server::start(|_| {
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(DB_DATA.as_bytes()).unwrap();
+        response("200 OK", "", &encoder.finish().unwrap())
+    });
+    let cache_dir = tempfile::tempdir().unwrap();
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .compression(Compression::Gzip)
+        .cache_dir(cache_dir.path())
+        .size_limit(DB_DATA.len() as u64 / 2);
+
+    let err = source.fetch().unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+    assert!(!cache_dir.path().join("pci.ids").exists());
+}
+
+#[cfg(feature = "xz")]
+#[test]
+fn decompressed_xz_size_limit() {
+    use pciid_parser::compression::Compression;
+
+    // A vendor followed by 4 MiB of empty lines, which the xz tool compresses to a few hundred bytes
+    let server = Server::start(|_| response("200 OK", "", include_bytes!("large.ids.xz")));
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .compression(Compression::Xz)
+        .size_limit(1024 * 1024);
+
+    let err = source.fetch().unwrap_err();
+    assert!(matches!(err, Error::Io(ref err) if err.to_string().contains("size limit")));
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn bzip2_download() {
+    use bzip2::{write::BzEncoder, Compression as Level};
+    use pciid_parser::compression::Compression;
+
+    let server = Server::start(|request| {
+        if request.starts_with("get /pci.ids.bz2 ") {
+            let mut encoder = BzEncoder::new(Vec::new(), Level::fast());
+            encoder.write_all(DB_DATA.as_bytes()).unwrap();
+            response("200 OK", "", &encoder.finish().unwrap())
+        } else {
+            response("404 Not Found", "", b"")
+        }
+    });
+    let source = OnlineSource::new()
+        .base_url(&server.url)
+        .compression(Compression::Bzip2);
+
+    assert_eq!(source.fetch().unwrap(), expected());
+}
+
+#[cfg(not(feature = "bzip2"))]
+#[test]
+fn compression_feature_disabled() {
+    use pciid_parser::compression::Compression;
+
+    let source = OnlineSource::new()
+        .base_url("http://127.0.0.1:9")
+        .compression(Compression::Bzip2);
+
+    let err = source.fetch().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedCompression(Compression::Bzip2)
+    ));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn fetch_async() {
+    let server = Server::start(|_| response("200 OK", "", DB_DATA.as_bytes()));
+    let source = OnlineSource::new().base_url(&server.url);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let db = runtime.block_on(source.fetch_async()).unwrap();
+    assert_eq!(db, expected());
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "std")]
+use pciid_parser::{overlay::Source, schema::EntryId, Database};
+use pretty_assertions::assert_eq;
+use std::{fs, io::Cursor};
+
+const OVERLAY: &str = "\
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t7fff  Prerelease GPU
+\t\t1da2 0001  Prerelease GPU Pulse
+\t67df  Ellesmere (patched)
+C 12  Processing accelerators
+\t01  AI inference accelerator
+\t\t01  Custom interface
+";
+
+#[test]
+fn apply_overlay() {
+    let mut db = Database::read().unwrap();
+    db.apply_overlay(Cursor::new(OVERLAY)).unwrap();
+
+    let info = db.get_device_info(0x1002, 0x7fff, 0x1da2, 0x0001);
+    assert_eq!(info.device_name, Some("Prerelease GPU"));
+    assert_eq!(info.subdevice_name, Some("Prerelease GPU Pulse"));
+
+    let info = db.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(info.device_name, Some("Ellesmere (patched)"));
+    // Entries not mentioned by the overlay are kept
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+
+    let class_info = db.get_class_info(0x12, 0x01, 0x01);
+    assert_eq!(class_info.prog_if_name, Some("Custom interface"));
+
+    let overlay = Some(Source::Overlay {
+        index: 0,
+        path: None,
+    });
+    assert_eq!(
+        db.source_of(EntryId::Device {
+            vendor: 0x1002,
+            device: 0x67df
+        }),
+        overlay
+    );
+    assert_eq!(
+        db.source_of(EntryId::Subdevice {
+            vendor: 0x1002,
+            device: 0x67df,
+            subvendor: 0x1da2,
+            subdevice: 0xe387
+        }),
+        Some(Source::Base)
+    );
+    assert_eq!(
+        db.source_of(EntryId::ProgIf {
+            class: 0x12,
+            subclass: 0x01,
+            prog_if: 0x01
+        }),
+        overlay
+    );
+    assert_eq!(db.source_of(EntryId::Vendor(0x0002)), None);
+}
+
+#[test]
+fn rename_entries() {
+    let mut db = Database::read().unwrap();
+    let overlay = "1002  AMD\n10de  NVIDIA Corporation\n";
+    db.apply_overlay(Cursor::new(overlay)).unwrap();
+
+    // Entries without nested entries in the overlay are renamed
+    assert_eq!(db.get_name(EntryId::Vendor(0x1002)), Some("AMD"));
+    assert_eq!(
+        db.source_of(EntryId::Vendor(0x1002)),
+        Some(Source::Overlay {
+            index: 0,
+            path: None
+        })
+    );
+    // Repeating the same name doesn't change the source
+    assert_eq!(db.source_of(EntryId::Vendor(0x10de)), Some(Source::Base));
+}
+
+#[test]
+fn failed_overlay_keeps_database() {
+    let mut db = Database::read().unwrap();
+    let broken = "1002  AMD\n\t7fff  Prerelease GPU\n\t7ffe Broken\n";
+
+    assert!(db.apply_overlay(Cursor::new(broken)).is_err());
+    assert_eq!(
+        db.get_name(EntryId::Device {
+            vendor: 0x1002,
+            device: 0x7fff
+        }),
+        None
+    );
+    assert_eq!(db.source_of(EntryId::Vendor(0x1002)), Some(Source::Base));
+}
+
+#[test]
+fn read_with_overlays() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+
+    fs::write(first.path().join("10-amd.ids"), OVERLAY).unwrap();
+    fs::write(
+        first.path().join("20-amd.ids"),
+        "1002  AMD\n\t7fff  Prerelease GPU (renamed)\n",
+    )
+    .unwrap();
+    fs::write(first.path().join("ignored.txt"), "1002  Ignored\n").unwrap();
+    fs::write(second.path().join("vendor.ids"), "0002  Test vendor\n").unwrap();
+
+    let db =
+        Database::read_with_overlays([first.path(), &first.path().join("missing"), second.path()])
+            .unwrap();
+
+    let device = EntryId::Device {
+        vendor: 0x1002,
+        device: 0x7fff,
+    };
+    assert_eq!(db.get_name(device), Some("Prerelease GPU (renamed)"));
+    // The vendor is only repeated as the parent of the devices
+    assert_eq!(
+        db.get_name(EntryId::Vendor(0x1002)),
+        Some("Advanced Micro Devices, Inc. [AMD/ATI]")
+    );
+    assert_eq!(db.source_of(EntryId::Vendor(0x1002)), Some(Source::Base));
+
+    let second_file = second.path().join("vendor.ids");
+    assert_eq!(
+        db.source_of(EntryId::Vendor(0x0002)),
+        Some(Source::Overlay {
+            index: 2,
+            path: Some(&second_file)
+        })
+    );
+    assert_eq!(
+        db.source_of(device),
+        Some(Source::Overlay {
+            index: 1,
+            path: Some(&first.path().join("20-amd.ids"))
+        })
+    );
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "std")]
+use pciid_parser::{schema::EntryId, search::Match, Database};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn search_devices() {
+    let db = Database::read().unwrap();
+    let entries: Vec<_> = db.search("NAVI 21 [").map(|found| found.entry).collect();
+
+    assert_eq!(
+        entries,
+        [
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73a1
+            },
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73a5
+            },
+            EntryId::Subdevice {
+                vendor: 0x1002,
+                device: 0x73a5,
+                subvendor: 0x1849,
+                subdevice: 0x5230
+            },
+            EntryId::Subdevice {
+                vendor: 0x1002,
+                device: 0x73a5,
+                subvendor: 0x1da2,
+                subdevice: 0x441d
+            },
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73ae
+            },
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73af
+            },
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73bf
+            },
+        ]
+    );
+}
+
+#[test]
+fn search_classes() {
+    let db = Database::read().unwrap();
+    let matches: Vec<_> = db.search("vga compatible").collect();
+
+    assert_eq!(
+        matches,
+        [
+            Match {
+                entry: EntryId::Device {
+                    vendor: 0x1139,
+                    device: 0x0001
+                },
+                name: "VGA Compatible 3D Graphics"
+            },
+            Match {
+                entry: EntryId::SubClass {
+                    class: 0x00,
+                    subclass: 0x01
+                },
+                name: "VGA compatible unclassified device"
+            },
+            Match {
+                entry: EntryId::SubClass {
+                    class: 0x03,
+                    subclass: 0x00
+                },
+                name: "VGA compatible controller"
+            },
+        ]
+    );
+}
+
+#[test]
+fn search_non_ascii() {
+    let db: Database = "1234  ÄRGER GmbH\n".parse().unwrap();
+    assert_eq!(db.search("ärger").count(), 1);
+    assert_eq!(db.search("gmbh").count(), 1);
+    assert_eq!(db.search("ärgerlich").count(), 0);
+}
+
+#[test]
+fn entries_cover_database() {
+    let db = Database::read().unwrap();
+    assert_eq!(db.entries().count(), db.search("").count());
+
+    for found in db.entries() {
+        assert_eq!(db.get_name(found.entry), Some(found.name));
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn search_regex() {
+    let db = Database::read().unwrap();
+    let regex = regex::Regex::new(r"^Navi 2\d USB$").unwrap();
+    let entries: Vec<_> = db.search_regex(&regex).map(|found| found.entry).collect();
+
+    assert_eq!(
+        entries,
+        [
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73a4
+            },
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73c4
+            },
+            EntryId::Device {
+                vendor: 0x1002,
+                device: 0x73e4
+            },
+        ]
+    );
+}
+
+#[test]
+fn fuzzy_search_aliases() {
+    let db = Database::read().unwrap();
+
+    let matches = db.fuzzy_search("RX 580");
+    assert_eq!(
+        matches[0].entry,
+        EntryId::Device {
+            vendor: 0x1002,
+            device: 0x67df
+        }
+    );
+    assert_eq!(matches[0].matched, "Radeon RX 580");
+    assert!(matches
+        .windows(2)
+        .all(|pair| pair[0].score >= pair[1].score));
+
+    let matches = db.fuzzy_search("GTX 1080 Ti");
+    assert_eq!(
+        matches[0].entry,
+        EntryId::Device {
+            vendor: 0x10de,
+            device: 0x1b06
+        }
+    );
+    assert_eq!(matches[0].matched, "GeForce GTX 1080 Ti");
+    // Every word of the query is required
+    assert!(matches.iter().all(|found| found.matched.contains("Ti")));
+}
+
+#[test]
+fn fuzzy_search_alias_groups() {
+    let db: Database = "1002  AMD\n\t73bf  Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]\n"
+        .parse()
+        .unwrap();
+
+    for (query, expected) in [
+        ("6800", "Radeon RX 6800"),
+        ("rx 6800 xt", "Radeon RX 6800 XT"),
+        ("radeon 6900", "Radeon RX 6900 XT"),
+        ("navi", "Navi 21"),
+        ("rx6900", "Radeon RX 6900 XT"),
+    ] {
+        let matches = db.fuzzy_search(query);
+        assert_eq!(matches.len(), 1, "{query}");
+        assert_eq!(matches[0].matched, expected, "{query}");
+    }
+
+    assert!(db.fuzzy_search("6950").is_empty());
+    assert!(db.fuzzy_search("").is_empty());
+}
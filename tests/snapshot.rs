@@ -0,0 +1,154 @@
+#![cfg(feature = "std")]
+use pciid_parser::{Database, Error};
+use pretty_assertions::assert_eq;
+use std::{fs, io::Cursor, thread, time::Duration};
+
+const DB_DATA: &[u8] = include_bytes!("pci.ids");
+
+fn snapshot_bytes(db: &Database) -> Vec<u8> {
+    let mut data = Vec::new();
+    db.save_snapshot(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let db = Database::parse_db(Cursor::new(DB_DATA)).unwrap();
+    let snapshot = Database::load_snapshot(Cursor::new(snapshot_bytes(&db))).unwrap();
+
+    assert_eq!(snapshot.metadata(), &db.metadata);
+    assert_eq!(snapshot.source_mtime(), None);
+
+    for (vendor_id, vendor) in &db.vendors {
+        for (device_id, device) in &vendor.devices {
+            for (subdevice_id, subdevice_name) in &device.subdevices {
+                let info = snapshot.get_device_info(
+                    *vendor_id,
+                    *device_id,
+                    subdevice_id.subvendor,
+                    subdevice_id.subdevice,
+                );
+                let expected = db.get_device_info(
+                    *vendor_id,
+                    *device_id,
+                    subdevice_id.subvendor,
+                    subdevice_id.subdevice,
+                );
+                assert_eq!(info.vendor_name, Some(vendor.name.as_str()));
+                assert_eq!(info.device_name, Some(device.name.as_str()));
+                assert_eq!(info.subvendor_name, expected.subvendor_name);
+                assert_eq!(info.subdevice_name, Some(subdevice_name.as_str()));
+            }
+        }
+    }
+
+    for (class_id, class) in &db.classes {
+        for (subclass_id, subclass) in &class.subclasses {
+            for (prog_if_id, prog_if_name) in &subclass.prog_ifs {
+                let info = snapshot.get_class_info(*class_id, *subclass_id, *prog_if_id);
+                assert_eq!(info.class_name, Some(class.name.as_str()));
+                assert_eq!(info.subclass_name, Some(subclass.name.as_str()));
+                assert_eq!(info.prog_if_name, Some(prog_if_name.as_str()));
+            }
+        }
+    }
+}
+
+#[test]
+fn snapshot_lookups() {
+    let db = Database::parse_db(Cursor::new(DB_DATA)).unwrap();
+    let snapshot = Database::load_snapshot(Cursor::new(snapshot_bytes(&db))).unwrap();
+
+    let info = snapshot.get_device_info(0x1002, 0x67df, 0x1da2, 0xe387);
+    assert_eq!(
+        info.vendor_name,
+        Some("Advanced Micro Devices, Inc. [AMD/ATI]")
+    );
+    assert_eq!(
+        info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(info.subvendor_name, Some("Sapphire Technology Limited"));
+    assert_eq!(info.subdevice_name, Some("Radeon RX 580 Pulse 4GB"));
+
+    let info = snapshot.get_device_info(0x1002, 0x0001, 0, 0);
+    assert_eq!(info.device_name, None);
+
+    let info = snapshot.get_class_info_by_code(0x03_00_00);
+    assert_eq!(info.class_name, Some("Display controller"));
+    assert_eq!(info.subclass_name, Some("VGA compatible controller"));
+    assert_eq!(info.prog_if_name, Some("VGA controller"));
+    assert_eq!(
+        snapshot.get_class_info_by_code(0x01_03_00_00).class_name,
+        None
+    );
+}
+
+#[test]
+fn reject_corrupted_snapshot() {
+    let db = Database::parse_db(Cursor::new(DB_DATA)).unwrap();
+    let mut data = snapshot_bytes(&db);
+
+    let last = data.len() - 1;
+    data[last] ^= 0xff;
+    let err = Database::load_snapshot(Cursor::new(&data)).unwrap_err();
+    assert!(matches!(err, Error::InvalidSnapshot("checksum mismatch")));
+
+    data.truncate(100);
+    let err = Database::load_snapshot(Cursor::new(&data)).unwrap_err();
+    assert!(matches!(err, Error::InvalidSnapshot("unexpected length")));
+
+    let err = Database::load_snapshot(Cursor::new(DB_DATA)).unwrap_err();
+    assert!(matches!(err, Error::InvalidSnapshot("not a snapshot")));
+}
+
+#[test]
+fn load_snapshot_or_parse() {
+    let dir = tempfile::tempdir().unwrap();
+    let source_path = dir.path().join("pci.ids");
+    let snapshot_path = dir.path().join("pci.ids.snapshot");
+    fs::write(&source_path, DB_DATA).unwrap();
+
+    // The snapshot is created on first use
+    let snapshot = Database::load_snapshot_or_parse(&snapshot_path, &source_path).unwrap();
+    assert!(snapshot_path.exists());
+    assert_eq!(
+        snapshot.source_mtime(),
+        Some(fs::metadata(&source_path).unwrap().modified().unwrap())
+    );
+    assert!(!snapshot.is_stale(&source_path).unwrap());
+
+    let cached = Database::load_snapshot_or_parse(&snapshot_path, &source_path).unwrap();
+    assert_eq!(cached.source_mtime(), snapshot.source_mtime());
+
+    // Updating the source makes the snapshot stale
+    thread::sleep(Duration::from_millis(20));
+    fs::write(&source_path, "1234  Updated vendor\n").unwrap();
+    assert!(cached.is_stale(&source_path).unwrap());
+
+    let updated = Database::load_snapshot_or_parse(&snapshot_path, &source_path).unwrap();
+    assert_eq!(
+        updated.get_device_info(0x1234, 0, 0, 0).vendor_name,
+        Some("Updated vendor")
+    );
+    assert_eq!(updated.get_device_info(0x1002, 0, 0, 0).vendor_name, None);
+}
+
+#[test]
+fn load_snapshot_without_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let source_path = dir.path().join("pci.ids");
+    let snapshot_path = dir.path().join("pci.ids.snapshot");
+    fs::write(&source_path, DB_DATA).unwrap();
+    Database::load_snapshot_or_parse(&snapshot_path, &source_path).unwrap();
+
+    // A valid snapshot is still used when the source is gone
+    fs::remove_file(&source_path).unwrap();
+    let snapshot = Database::load_snapshot_or_parse(&snapshot_path, &source_path).unwrap();
+    assert_eq!(
+        snapshot.get_device_info(0x1002, 0, 0, 0).vendor_name,
+        Some("Advanced Micro Devices, Inc. [AMD/ATI]")
+    );
+    // Only the snapshot is left in the directory
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+use pciid_parser::{
+    sysfs::{self, PciAddress},
+    Database,
+};
+use pretty_assertions::assert_eq;
+use std::{fs, path::Path};
+
+fn write_device(root: &Path, address: &str, attributes: &[(&str, &str)]) {
+    let dir = root.join(address);
+    fs::create_dir(&dir).unwrap();
+
+    for (name, value) in attributes {
+        fs::write(dir.join(name), format!("{value}\n")).unwrap();
+    }
+}
+
+#[test]
+fn enumerate_fake_tree() {
+    let db = Database::read().unwrap();
+    let root = tempfile::tempdir().unwrap();
+
+    write_device(
+        root.path(),
+        "0000:03:00.0",
+        &[
+            ("vendor", "0x1002"),
+            ("device", "0x67df"),
+            ("subsystem_vendor", "0x1da2"),
+            ("subsystem_device", "0xe387"),
+            ("class", "0x030000"),
+            ("revision", "0xe7"),
+        ],
+    );
+    write_device(
+        root.path(),
+        "0000:00:00.0",
+        &[
+            ("vendor", "0x1022"),
+            ("device", "0x1480"),
+            ("class", "0x060000"),
+        ],
+    );
+    fs::write(root.path().join("not-a-device"), "").unwrap();
+
+    let devices = sysfs::devices_at(&db, root.path()).unwrap();
+    assert_eq!(devices.len(), 2);
+
+    let bridge = &devices[0];
+    assert_eq!(bridge.address.to_string(), "0000:00:00.0");
+    assert_eq!(bridge.subsystem_vendor_id, 0);
+    assert_eq!(bridge.revision, 0);
+    assert_eq!(bridge.class_info.subclass_name, Some("Host bridge"));
+
+    let gpu = &devices[1];
+    assert_eq!(
+        gpu.address,
+        PciAddress {
+            domain: 0,
+            bus: 3,
+            device: 0,
+            function: 0
+        }
+    );
+    assert_eq!((gpu.vendor_id, gpu.device_id), (0x1002, 0x67df));
+    assert_eq!(gpu.class_code, 0x030000);
+    assert_eq!(gpu.revision, 0xe7);
+    assert_eq!(
+        gpu.device_info.device_name,
+        Some("Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]")
+    );
+    assert_eq!(
+        gpu.device_info.subdevice_name,
+        Some("Radeon RX 580 Pulse 4GB")
+    );
+    assert_eq!(
+        gpu.class_info.subclass_name,
+        Some("VGA compatible controller")
+    );
+}
+
+#[test]
+fn malformed_attribute() {
+    let db = Database::read().unwrap();
+    let root = tempfile::tempdir().unwrap();
+
+    write_device(
+        root.path(),
+        "0000:03:00.0",
+        &[
+            ("vendor", "1002"),
+            ("device", "0x67df"),
+            ("class", "0x030000"),
+        ],
+    );
+
+    assert!(sysfs::devices_at(&db, root.path()).is_err());
+}
+
+#[test]
+fn parse_address() {
+    let address = PciAddress::parse("10000:e1:1f.7").unwrap();
+    assert_eq!(address.domain, 0x10000);
+    assert_eq!(address.bus, 0xe1);
+    assert_eq!(address.device, 0x1f);
+    assert_eq!(address.function, 7);
+    assert_eq!(address.to_string(), "10000:e1:1f.7");
+
+    assert_eq!(PciAddress::parse("03:00.0"), None);
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+use pciid_parser::Database;
+use pretty_assertions::assert_eq;
+use std::{fs::File, io::Cursor};
+
+#[test]
+fn round_trip() {
+    let db = Database::parse_db(File::open("./tests/pci.ids").unwrap()).unwrap();
+
+    let mut output = Vec::new();
+    db.write_to(&mut output).unwrap();
+    let parsed = Database::parse_db(Cursor::new(&output)).unwrap();
+
+    assert_eq!(db, parsed);
+}
+
+#[test]
+fn canonical_format() {
+    let input = "\
+#\tVersion: 2023.09.22
+#\tDate:    2023-09-22 03:15:02
+1af4  Red Hat, Inc.
+\t1050  Virtio 1.0 GPU
+\t1000  Virtio network device
+\t\t1af4 0001  Virtio network device
+\t\t0000 0001  Virtio network device
+0001  SafeNet (wrong ID)
+C 03  Display controller
+\t80  Display controller
+\t00  VGA compatible controller
+\t\t01  8514 controller
+\t\t00  VGA controller
+";
+    let db = Database::parse_db(Cursor::new(input)).unwrap();
+
+    let mut output = Vec::new();
+    db.write_to(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    let entries: Vec<_> = output
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            "0001  SafeNet (wrong ID)",
+            "1af4  Red Hat, Inc.",
+            "\t1000  Virtio network device",
+            "\t\t0000 0001  Virtio network device",
+            "\t\t1af4 0001  Virtio network device",
+            "\t1050  Virtio 1.0 GPU",
+            "C 03  Display controller",
+            "\t00  VGA compatible controller",
+            "\t\t00  VGA controller",
+            "\t\t01  8514 controller",
+            "\t80  Display controller",
+        ]
+    );
+    assert!(output.contains("#\tVersion: 2023.09.22\n#\tDate:    2023-09-22 03:15:02\n"));
+}